@@ -690,6 +690,30 @@ pub enum AcceptedCountries {
     AllAccepted,
 }
 
+/// Result of verifying the credentials of a Merchant Connector Account
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MerchantConnectorHealthCheckResponse {
+    /// Unique ID of the connector
+    #[schema(example = "mca_5apGeP94tMts6rg3U3kR")]
+    pub merchant_connector_id: String,
+    /// Name of the connector
+    #[schema(example = "airwallex")]
+    pub connector_name: String,
+    /// Whether the credentials of the connector are valid
+    pub status: ConnectorHealthStatus,
+    /// Error returned by the connector when the credentials could not be verified
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectorHealthStatus {
+    Healthy,
+    Unhealthy,
+    /// The credentials of the connector cannot be verified without running a payment
+    NotSupported,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MerchantConnectorDeleteResponse {
     /// The identifier for the Merchant Account
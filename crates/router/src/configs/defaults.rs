@@ -56,6 +56,77 @@ impl Default for super::settings::Locker {
     }
 }
 
+impl Default for super::settings::ConnectorErrorStatus {
+    fn default() -> Self {
+        Self {
+            validation_error: 400,
+            rate_limited: 429,
+            connector_unavailable: 502,
+        }
+    }
+}
+
+impl Default for super::settings::AccessTokenConfig {
+    fn default() -> Self {
+        Self {
+            max_refresh_attempts: 3,
+            refresh_backoff_base_ms: 100,
+            lock_ttl_secs: 10,
+            lock_wait_timeout_ms: 2000,
+            lock_poll_interval_ms: 100,
+            refresh_skew_secs: 60,
+            max_retry_after_ms: 2000,
+            warm_pool: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl Default for super::settings::ConnectRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 2,
+            backoff_base_ms: 50,
+        }
+    }
+}
+
+impl Default for super::settings::WebhookOrderingConfig {
+    fn default() -> Self {
+        Self {
+            retention_secs: 86400,
+            connectors: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl Default for super::settings::InvalidCredentialsConfig {
+    fn default() -> Self {
+        Self {
+            marker_ttl_secs: 60,
+        }
+    }
+}
+
+impl Default for super::settings::ConnectorResponseConfig {
+    fn default() -> Self {
+        Self {
+            // 5 MiB
+            max_body_size_bytes: 5 * 1024 * 1024,
+        }
+    }
+}
+
+impl Default for super::settings::ConnectorRequestSampling {
+    fn default() -> Self {
+        Self {
+            sample_rate: 0,
+            flows: std::collections::HashMap::new(),
+            retention_secs: 900,
+            capture_on_failure: false,
+        }
+    }
+}
+
 impl Default for super::settings::SupportedConnectors {
     fn default() -> Self {
         Self {
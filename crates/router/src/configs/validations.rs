@@ -256,3 +256,91 @@ impl super::settings::ApiKeys {
         })
     }
 }
+
+impl super::settings::AccessTokenConfig {
+    pub fn validate(&self) -> Result<(), ApplicationError> {
+        use common_utils::fp_utils::when;
+
+        when(self.max_refresh_attempts == 0, || {
+            Err(ApplicationError::InvalidConfigurationValueError(
+                "access token max refresh attempts must be greater than 0".into(),
+            ))
+        })?;
+
+        when(self.lock_ttl_secs <= 0, || {
+            Err(ApplicationError::InvalidConfigurationValueError(
+                "access token lock ttl must be greater than 0".into(),
+            ))
+        })?;
+
+        when(self.lock_poll_interval_ms == 0, || {
+            Err(ApplicationError::InvalidConfigurationValueError(
+                "access token lock poll interval must be greater than 0".into(),
+            ))
+        })?;
+
+        when(self.refresh_skew_secs < 0, || {
+            Err(ApplicationError::InvalidConfigurationValueError(
+                "access token refresh skew must not be negative".into(),
+            ))
+        })?;
+
+        self.warm_pool
+            .iter()
+            .try_for_each(|(connector, warm_pool)| {
+                when(warm_pool.refresh_ahead_secs <= 0, || {
+                    Err(ApplicationError::InvalidConfigurationValueError(format!(
+                        "access token warm pool refresh ahead of {connector} must be greater than 0"
+                    )))
+                })
+            })
+    }
+}
+
+impl super::settings::ConnectRetryConfig {
+    pub fn validate(&self) -> Result<(), ApplicationError> {
+        use common_utils::fp_utils::when;
+
+        when(self.max_attempts == 0, || {
+            Err(ApplicationError::InvalidConfigurationValueError(
+                "connect retry max attempts must be greater than 0".into(),
+            ))
+        })
+    }
+}
+
+impl super::settings::WebhookOrderingConfig {
+    pub fn validate(&self) -> Result<(), ApplicationError> {
+        use common_utils::fp_utils::when;
+
+        when(self.retention_secs <= 0, || {
+            Err(ApplicationError::InvalidConfigurationValueError(
+                "webhook ordering retention must be greater than 0".into(),
+            ))
+        })
+    }
+}
+
+impl super::settings::InvalidCredentialsConfig {
+    pub fn validate(&self) -> Result<(), ApplicationError> {
+        use common_utils::fp_utils::when;
+
+        when(self.marker_ttl_secs < 0, || {
+            Err(ApplicationError::InvalidConfigurationValueError(
+                "invalid credentials marker ttl must not be negative".into(),
+            ))
+        })
+    }
+}
+
+impl super::settings::ConnectorResponseConfig {
+    pub fn validate(&self) -> Result<(), ApplicationError> {
+        use common_utils::fp_utils::when;
+
+        when(self.max_body_size_bytes == 0, || {
+            Err(ApplicationError::InvalidConfigurationValueError(
+                "connector response max body size must be greater than 0".into(),
+            ))
+        })
+    }
+}
@@ -4,6 +4,7 @@ use std::fmt::Debug;
 
 use common_utils::ext_traits::{ByteSliceExt, ValueExt};
 use error_stack::{IntoReport, ResultExt};
+use masking::PeekInterface;
 use transformers as airwallex;
 
 use super::utils::{AccessTokenRequestInfo, RefundsRequestData};
@@ -24,6 +25,7 @@ use crate::{
     types::{
         self,
         api::{self, ConnectorCommon, ConnectorCommonExt},
+        storage::enums,
         ErrorResponse, Response, RouterData,
     },
     utils::{self, crypto, BytesExt},
@@ -52,7 +54,7 @@ where
 
         let auth_header = (
             headers::AUTHORIZATION.to_string(),
-            format!("Bearer {}", access_token.token).into_masked(),
+            format!("Bearer {}", access_token.token.peek()).into_masked(),
         );
 
         headers.push(auth_header);
@@ -73,6 +75,46 @@ impl ConnectorCommon for Airwallex {
         connectors.airwallex.base_url.as_ref()
     }
 
+    fn capabilities(&self) -> api::ConnectorCapabilities {
+        api::ConnectorCapabilities {
+            authorize: true,
+            capture: true,
+            refund: true,
+            webhooks: true,
+            ..api::ConnectorCapabilities::for_connector(self.id())
+        }
+    }
+
+    fn validate_auth_type(
+        &self,
+        auth_type: &types::ConnectorAuthType,
+    ) -> CustomResult<(), errors::ConnectorError> {
+        // The api key and the client id are both required to obtain an access token
+        match auth_type {
+            types::ConnectorAuthType::BodyKey { api_key, key1 } => {
+                if api_key.is_empty() {
+                    return Err(errors::ConnectorError::MissingRequiredField {
+                        field_name: "api_key",
+                    }
+                    .into());
+                }
+                if key1.is_empty() {
+                    return Err(errors::ConnectorError::MissingRequiredField {
+                        field_name: "key1",
+                    }
+                    .into());
+                }
+                Ok(())
+            }
+            _ => Err(errors::ConnectorError::FailedToObtainAuthType.into()),
+        }
+    }
+
+    fn get_invalid_credentials_error_codes(&self) -> &'static [&'static str] {
+        // Returned when the client id or the api key is rejected while obtaining an access token
+        &["credentials_invalid"]
+    }
+
     fn build_error_response(
         &self,
         res: Response,
@@ -313,7 +355,14 @@ impl ConnectorIntegration<api::Authorize, types::PaymentsAuthorizeData, types::P
             None,
         )
         .await?;
-        router_data.reference_id = resp.reference_id;
+        match resp.response {
+            // The payment intent id is threaded into the confirm request through the reference id
+            Ok(_) => router_data.reference_id = resp.reference_id,
+            Err(error_response) => {
+                router_data.status = enums::AttemptStatus::Failure;
+                router_data.response = Err(error_response);
+            }
+        }
         Ok(())
     }
 
@@ -363,6 +412,11 @@ impl ConnectorIntegration<api::Authorize, types::PaymentsAuthorizeData, types::P
         req: &types::PaymentsAuthorizeRouterData,
         connectors: &settings::Connectors,
     ) -> CustomResult<Option<services::Request>, errors::ConnectorError> {
+        // The payment intent could not be created, its error response is returned without
+        // confirming the payment
+        if req.reference_id.is_none() {
+            return Ok(None);
+        }
         Ok(Some(
             services::RequestBuilder::new()
                 .method(services::Method::Post)
@@ -825,7 +879,7 @@ impl ConnectorIntegration<api::Execute, types::RefundsData, types::RefundsRespon
         let response: airwallex::RefundResponse = res
             .response
             .parse_struct("airwallex RefundResponse")
-            .change_context(errors::ConnectorError::RequestEncodingFailed)?;
+            .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
         types::ResponseRouterData {
             response,
             data: data.clone(),
@@ -866,7 +920,7 @@ impl ConnectorIntegration<api::RSync, types::RefundsData, types::RefundsResponse
         Ok(format!(
             "{}{}{}",
             self.base_url(connectors),
-            "/api/v1/pa/refunds/",
+            "api/v1/pa/refunds/",
             req.request.get_connector_refund_id()?
         ))
     }
@@ -1038,6 +1092,30 @@ impl api::IncomingWebhook for Airwallex {
         Ok(api::IncomingWebhookEvent::try_from(details.name)?)
     }
 
+    fn get_webhook_delivery_id(
+        &self,
+        request: &api::IncomingWebhookRequestDetails<'_>,
+    ) -> CustomResult<Option<String>, errors::ConnectorError> {
+        let details: airwallex::AirwallexWebhookData = request
+            .body
+            .parse_struct("airwallexWebhookData")
+            .change_context(errors::ConnectorError::WebhookBodyDecodingFailed)?;
+        Ok(details.id)
+    }
+
+    fn get_webhook_event_timestamp(
+        &self,
+        request: &api::IncomingWebhookRequestDetails<'_>,
+    ) -> CustomResult<Option<i64>, errors::ConnectorError> {
+        let details: airwallex::AirwallexWebhookData = request
+            .body
+            .parse_struct("airwallexWebhookData")
+            .change_context(errors::ConnectorError::WebhookBodyDecodingFailed)?;
+        Ok(details
+            .created_at
+            .map(|created_at| created_at.assume_utc().unix_timestamp()))
+    }
+
     fn get_webhook_resource_object(
         &self,
         request: &api::IncomingWebhookRequestDetails<'_>,
@@ -77,6 +77,14 @@ pub enum AirwallexPaymentOptions {
 #[derive(Debug, Serialize, Eq, PartialEq)]
 pub struct AirwallexCardPaymentOptions {
     auto_capture: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    three_ds_action: Option<AirwallexThreeDsAction>,
+}
+
+#[derive(Debug, Serialize, Eq, PartialEq)]
+pub enum AirwallexThreeDsAction {
+    #[serde(rename = "FORCE_3DS")]
+    Force3Ds,
 }
 
 impl TryFrom<&types::PaymentsAuthorizeRouterData> for AirwallexPaymentsRequest {
@@ -91,6 +99,8 @@ impl TryFrom<&types::PaymentsAuthorizeRouterData> for AirwallexPaymentsRequest {
                             item.request.capture_method,
                             Some(enums::CaptureMethod::Automatic) | None
                         ),
+                        three_ds_action: (item.auth_type == enums::AuthenticationType::ThreeDs)
+                            .then_some(AirwallexThreeDsAction::Force3Ds),
                     }));
                 Ok(AirwallexPaymentMethod::Card(AirwallexCard {
                     card: AirwallexCardDetails {
@@ -119,7 +129,7 @@ impl TryFrom<&types::PaymentsAuthorizeRouterData> for AirwallexPaymentsRequest {
 pub struct AirwallexAuthUpdateResponse {
     #[serde(with = "common_utils::custom_serde::iso8601")]
     expires_at: PrimitiveDateTime,
-    token: String,
+    token: Secret<String>,
 }
 
 impl<F, T> TryFrom<types::ResponseRouterData<F, AirwallexAuthUpdateResponse, T, types::AccessToken>>
@@ -134,6 +144,7 @@ impl<F, T> TryFrom<types::ResponseRouterData<F, AirwallexAuthUpdateResponse, T,
             response: Ok(types::AccessToken {
                 token: item.response.token,
                 expires,
+                access_token_created_at: None,
             }),
             ..item.data
         })
@@ -246,10 +257,11 @@ fn get_payment_status(response: &AirwallexPaymentsResponse) -> enums::AttemptSta
         AirwallexPaymentStatus::RequiresCustomerAction => response.next_action.as_ref().map_or(
             enums::AttemptStatus::AuthenticationPending,
             |next_action| match next_action.stage {
-                AirwallexNextActionStage::WaitingDeviceDataCollection => {
+                Some(AirwallexNextActionStage::WaitingDeviceDataCollection) => {
                     enums::AttemptStatus::DeviceDataCollectionPending
                 }
-                AirwallexNextActionStage::WaitingUserInfoInput => {
+                // A plain redirect next action carries no stage and waits on the customer
+                Some(AirwallexNextActionStage::WaitingUserInfoInput) | None => {
                     enums::AttemptStatus::AuthenticationPending
                 }
             },
@@ -266,6 +278,14 @@ pub enum AirwallexNextActionStage {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AirwallexNextActionType {
+    Redirect,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct AirwallexRedirectFormData {
     #[serde(rename = "JWT")]
     jwt: Option<String>,
@@ -274,14 +294,20 @@ pub struct AirwallexRedirectFormData {
     token: Option<String>,
     provider: Option<String>,
     version: Option<String>,
+    // Any other form field sent by the connector, these have to be posted back as is
+    #[serde(flatten)]
+    additional_fields: std::collections::HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AirwallexPaymentsNextAction {
+    #[serde(rename = "type")]
+    next_action_type: Option<AirwallexNextActionType>,
     url: Url,
     method: services::Method,
+    #[serde(default)]
     data: AirwallexRedirectFormData,
-    stage: AirwallexNextActionStage,
+    stage: Option<AirwallexNextActionStage>,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -298,35 +324,36 @@ pub struct AirwallexPaymentsResponse {
 fn get_redirection_form(
     response_url_data: AirwallexPaymentsNextAction,
 ) -> Option<services::RedirectForm> {
+    let is_redirect = matches!(
+        response_url_data.next_action_type,
+        Some(AirwallexNextActionType::Redirect)
+    );
+    let data = response_url_data.data;
+    let form_fields = [
+        ("JWT", data.jwt),
+        ("threeDSMethodData", data.three_ds_method_data),
+        ("token", data.token),
+        ("provider", data.provider),
+        ("version", data.version),
+    ]
+    .into_iter()
+    //Some form fields might be empty based on the authentication type by the connector,
+    //a redirect form is posted with only the fields that were sent
+    .filter(|(_, value)| !is_redirect || value.is_some())
+    .map(|(key, value)| (key.to_string(), value.unwrap_or_default()))
+    .chain(
+        data.additional_fields
+            .into_iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            }),
+    )
+    .collect();
     Some(services::RedirectForm::Form {
         endpoint: response_url_data.url.to_string(),
         method: response_url_data.method,
-        form_fields: std::collections::HashMap::from([
-            //Some form fields might be empty based on the authentication type by the connector
-            (
-                "JWT".to_string(),
-                response_url_data.data.jwt.unwrap_or_default(),
-            ),
-            (
-                "threeDSMethodData".to_string(),
-                response_url_data
-                    .data
-                    .three_ds_method_data
-                    .unwrap_or_default(),
-            ),
-            (
-                "token".to_string(),
-                response_url_data.data.token.unwrap_or_default(),
-            ),
-            (
-                "provider".to_string(),
-                response_url_data.data.provider.unwrap_or_default(),
-            ),
-            (
-                "version".to_string(),
-                response_url_data.data.version.unwrap_or_default(),
-            ),
-        ]),
+        form_fields,
     })
 }
 
@@ -359,7 +386,7 @@ impl<F, T>
                     // If the connector sends waiting for DDC and our status is already DDC Pending
                     // that means we initiated the call to collect the data and now we expect a different response
                     (
-                        AirwallexNextActionStage::WaitingDeviceDataCollection,
+                        Some(AirwallexNextActionStage::WaitingDeviceDataCollection),
                         enums::AttemptStatus::DeviceDataCollectionPending,
                         _
                     )
@@ -425,7 +452,6 @@ impl<F> TryFrom<&types::RefundsRouterData<F>> for AirwallexRefundRequest {
 }
 
 // Type definition for Refund Response
-#[allow(dead_code)]
 #[derive(Debug, Serialize, Default, Deserialize, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RefundStatus {
@@ -495,9 +521,16 @@ impl TryFrom<types::RefundsResponseRouterData<api::RSync, RefundResponse>>
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AirwallexWebhookData {
+    pub id: Option<String>,
     pub source_id: Option<String>,
     pub name: AirwallexWebhookEventType,
     pub data: AirwallexObjectData,
+    #[serde(
+        rename = "created_at",
+        default,
+        with = "common_utils::custom_serde::iso8601::option"
+    )]
+    pub created_at: Option<PrimitiveDateTime>,
 }
 
 #[derive(Debug, Deserialize, strum::Display, PartialEq)]
@@ -687,3 +720,42 @@ impl From<AirwallexDisputeStage> for api_models::enums::DisputeStage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::panic)]
+
+    use super::*;
+
+    #[test]
+    fn test_redirect_next_action_preserves_form_fields() {
+        let next_action: AirwallexPaymentsNextAction = serde_json::from_value(serde_json::json!({
+            "type": "redirect",
+            "method": "POST",
+            "url": "https://pci-api-demo.airwallex.com/pa/card3ds/redirect",
+            "data": {
+                "PaReq": "eJxVUk1z",
+                "TermUrl": "https://example.com/return",
+                "MD": "12345"
+            }
+        }))
+        .unwrap();
+
+        let form_fields = match get_redirection_form(next_action) {
+            Some(services::RedirectForm::Form { form_fields, .. }) => form_fields,
+            _ => panic!("expected a form redirection"),
+        };
+
+        assert_eq!(
+            form_fields,
+            std::collections::HashMap::from([
+                ("PaReq".to_string(), "eJxVUk1z".to_string()),
+                (
+                    "TermUrl".to_string(),
+                    "https://example.com/return".to_string()
+                ),
+                ("MD".to_string(), "12345".to_string()),
+            ])
+        );
+    }
+}
@@ -129,6 +129,7 @@ impl TryFrom<GlobalpayRefreshTokenResponse> for types::AccessToken {
         Ok(Self {
             token: item.token,
             expires: item.seconds_to_expire,
+            access_token_created_at: None,
         })
     }
 }
@@ -277,6 +278,7 @@ impl<F, T>
             response: Ok(types::AccessToken {
                 token: item.response.token,
                 expires: item.response.seconds_to_expire,
+                access_token_created_at: None,
             }),
             ..item.data
         })
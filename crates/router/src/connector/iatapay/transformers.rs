@@ -1,15 +1,18 @@
 use std::collections::HashMap;
 
+use masking::Secret;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    connector::utils::{PaymentsAuthorizeRequestData, RefundsRequestData, RouterData},
+    connector::utils::{
+        AccessTokenRequestInfo, PaymentsAuthorizeRequestData, RefundsRequestData, RouterData,
+    },
     core::errors,
     services,
     types::{self, api, storage::enums},
 };
 
-// Every access token will be valid for 5 minutes. It contains grant_type and scope for different type of access, but for our usecases it should be only 'client_credentials' and 'payment' resp(as per doc) for all type of api call.
+// Every access token will be valid for 5 minutes. It contains grant_type and scope for different type of access, but for our usecases it should be only 'client_credentials' and 'payment' resp(as per doc) for all type of api call, unless they are overridden in the access token request.
 #[derive(Debug, Serialize)]
 pub struct IatapayAuthUpdateRequest {
     grant_type: String,
@@ -17,17 +20,17 @@ pub struct IatapayAuthUpdateRequest {
 }
 impl TryFrom<&types::RefreshTokenRouterData> for IatapayAuthUpdateRequest {
     type Error = error_stack::Report<errors::ConnectorError>;
-    fn try_from(_item: &types::RefreshTokenRouterData) -> Result<Self, Self::Error> {
+    fn try_from(item: &types::RefreshTokenRouterData) -> Result<Self, Self::Error> {
         Ok(Self {
-            grant_type: "client_credentials".to_string(),
-            scope: "payment".to_string(),
+            grant_type: item.get_grant_type_or("client_credentials"),
+            scope: item.get_scope_or("payment"),
         })
     }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct IatapayAuthUpdateResponse {
-    pub access_token: String,
+    pub access_token: Secret<String>,
     pub token_type: String,
     pub expires_in: i64,
     pub scope: String,
@@ -45,6 +48,7 @@ impl<F, T> TryFrom<types::ResponseRouterData<F, IatapayAuthUpdateResponse, T, ty
             response: Ok(types::AccessToken {
                 token: item.response.access_token,
                 expires: item.response.expires_in,
+                access_token_created_at: None,
             }),
             ..item.data
         })
@@ -268,7 +268,7 @@ impl TryFrom<&types::RefreshTokenRouterData> for PayuAuthUpdateRequest {
     type Error = error_stack::Report<errors::ConnectorError>;
     fn try_from(item: &types::RefreshTokenRouterData) -> Result<Self, Self::Error> {
         Ok(Self {
-            grant_type: "client_credentials".to_string(),
+            grant_type: item.get_grant_type_or("client_credentials"),
             client_id: item.get_request_id()?,
             client_secret: item.request.app_id.clone(),
         })
@@ -276,7 +276,7 @@ impl TryFrom<&types::RefreshTokenRouterData> for PayuAuthUpdateRequest {
 }
 #[derive(Default, Debug, Clone, Deserialize, PartialEq)]
 pub struct PayuAuthUpdateResponse {
-    pub access_token: String,
+    pub access_token: Secret<String>,
     pub token_type: String,
     pub expires_in: i64,
     pub grant_type: String,
@@ -293,6 +293,7 @@ impl<F, T> TryFrom<types::ResponseRouterData<F, PayuAuthUpdateResponse, T, types
             response: Ok(types::AccessToken {
                 token: item.response.access_token,
                 expires: item.response.expires_in,
+                access_token_created_at: None,
             }),
             ..item.data
         })
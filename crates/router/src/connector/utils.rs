@@ -39,6 +39,11 @@ type Error = error_stack::Report<errors::ConnectorError>;
 
 pub trait AccessTokenRequestInfo {
     fn get_request_id(&self) -> Result<String, Error>;
+    /// Grant type requested in the connector metadata, `default_grant_type` if none was requested
+    fn get_grant_type_or(&self, default_grant_type: &str) -> String;
+    /// Space separated scopes requested in the connector metadata, `default_scope` if none were
+    /// requested
+    fn get_scope_or(&self, default_scope: &str) -> String;
 }
 
 impl AccessTokenRequestInfo for types::RefreshTokenRouterData {
@@ -48,6 +53,21 @@ impl AccessTokenRequestInfo for types::RefreshTokenRouterData {
             .clone()
             .ok_or_else(missing_field_err("request.id"))
     }
+
+    fn get_grant_type_or(&self, default_grant_type: &str) -> String {
+        self.request
+            .grant_type
+            .clone()
+            .unwrap_or_else(|| default_grant_type.to_string())
+    }
+
+    fn get_scope_or(&self, default_scope: &str) -> String {
+        if self.request.scopes.is_empty() {
+            default_scope.to_string()
+        } else {
+            self.request.scopes.join(" ")
+        }
+    }
 }
 
 pub trait RouterData {
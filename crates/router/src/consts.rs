@@ -19,6 +19,9 @@ pub const DEFAULT_FULFILLMENT_TIME: i64 = 15 * 60;
 pub(crate) const NO_ERROR_MESSAGE: &str = "No error message";
 pub(crate) const NO_ERROR_CODE: &str = "No error code";
 pub(crate) const CONNECTOR_UNAUTHORIZED_ERROR: &str = "Authentication Error from the connector";
+pub(crate) const INVALID_CREDENTIALS_ERROR_CODE: &str = "invalid_credentials";
+pub(crate) const INVALID_CREDENTIALS_ERROR_MESSAGE: &str =
+    "The connector recently rejected the credentials, update the merchant connector account";
 
 // General purpose base64 engines
 pub(crate) const BASE64_ENGINE: base64::engine::GeneralPurpose =
@@ -10,13 +10,15 @@ use storage_models::enums;
 use uuid::Uuid;
 
 use crate::{
+    configs::settings,
     consts,
     core::{
         errors::{self, RouterResponse, RouterResult, StorageErrorExt},
-        payments::helpers,
+        payments::{self, helpers},
     },
     db::StorageInterface,
-    routes::metrics,
+    logger,
+    routes::{metrics, AppState},
     services::{self, api as service_api},
     types::{
         self, api,
@@ -436,8 +438,46 @@ fn validate_certificate_in_mca_metadata(
     Ok(())
 }
 
+/// Validate the authentication details with the connector, names that are not payment
+/// connectors (e.g. fraud and risk management connectors) are not validated here
+fn validate_connector_auth_type(
+    connectors: &settings::Connectors,
+    connector_name: &str,
+    auth_type: &types::ConnectorAuthType,
+) -> RouterResult<()> {
+    let connector_data = match api::ConnectorData::get_connector_by_name(
+        connectors,
+        connector_name,
+        api::GetToken::Connector,
+    ) {
+        Ok(connector_data) => connector_data,
+        Err(_) => return Ok(()),
+    };
+
+    connector_data
+        .connector
+        .validate_auth_type(auth_type)
+        .map_err(|error| {
+            let api_error = match error.current_context() {
+                errors::ConnectorError::MissingRequiredField { field_name } => {
+                    errors::ApiErrorResponse::MissingRequiredField {
+                        field_name: *field_name,
+                    }
+                }
+                _ => errors::ApiErrorResponse::InvalidDataFormat {
+                    field_name: "connector_account_details.auth_type".to_string(),
+                    expected_format: format!(
+                        "an authentication type supported by {connector_name}"
+                    ),
+                },
+            };
+            error.change_context(api_error)
+        })
+}
+
 pub async fn create_payment_connector(
     store: &dyn StorageInterface,
+    connectors: &settings::Connectors,
     req: api::MerchantConnectorCreate,
     merchant_id: &String,
 ) -> RouterResponse<api_models::admin::MerchantConnectorResponse> {
@@ -482,7 +522,7 @@ pub async fn create_payment_connector(
     };
 
     // Validate Merchant api details and return error if not in correct format
-    let _: types::ConnectorAuthType = req
+    let auth_type: types::ConnectorAuthType = req
         .connector_account_details
         .clone()
         .parse_value("ConnectorAuthType")
@@ -490,6 +530,7 @@ pub async fn create_payment_connector(
             field_name: "connector_account_details".to_string(),
             expected_format: "auth_type and api_key".to_string(),
         })?;
+    validate_connector_auth_type(connectors, &req.connector_name, &auth_type)?;
 
     let frm_configs = match req.frm_configs {
         Some(frm_value) => {
@@ -622,8 +663,115 @@ pub async fn list_payment_connectors(
     Ok(service_api::ApplicationResponse::Json(response))
 }
 
+pub async fn check_payment_connectors_health(
+    state: &AppState,
+    merchant_id: String,
+) -> RouterResponse<Vec<api_models::admin::MerchantConnectorHealthCheckResponse>> {
+    let store = &*state.store;
+    let key_store = store
+        .get_merchant_key_store_by_merchant_id(
+            &merchant_id,
+            &store.get_master_key().to_vec().into(),
+        )
+        .await
+        .to_not_found_response(errors::ApiErrorResponse::MerchantAccountNotFound)?;
+
+    // Validate merchant account
+    store
+        .find_merchant_account_by_merchant_id(&merchant_id, &key_store)
+        .await
+        .to_not_found_response(errors::ApiErrorResponse::MerchantAccountNotFound)?;
+
+    let merchant_connector_accounts = store
+        .find_merchant_connector_account_by_merchant_id_and_disabled_list(
+            &merchant_id,
+            false,
+            &key_store,
+        )
+        .await
+        .to_not_found_response(errors::ApiErrorResponse::InternalServerError)?;
+    let mut response = vec![];
+
+    for mca in merchant_connector_accounts {
+        response.push(check_payment_connector_health(state, &merchant_id, mca).await);
+    }
+
+    Ok(service_api::ApplicationResponse::Json(response))
+}
+
+/// Check the health of a single merchant connector account. Any failure to run the check is
+/// reported as an unhealthy connector, so that it does not fail the checks of other connectors.
+async fn check_payment_connector_health(
+    state: &AppState,
+    merchant_id: &str,
+    mca: domain::MerchantConnectorAccount,
+) -> api_models::admin::MerchantConnectorHealthCheckResponse {
+    let (status, error_message) =
+        match run_payment_connector_health_check(state, merchant_id, &mca).await {
+            Ok(Some(Ok(()))) => (api_models::admin::ConnectorHealthStatus::Healthy, None),
+            Ok(Some(Err(error_response))) => (
+                api_models::admin::ConnectorHealthStatus::Unhealthy,
+                Some(error_response.message),
+            ),
+            Ok(None) => (api_models::admin::ConnectorHealthStatus::NotSupported, None),
+            Err(error) => {
+                logger::error!(connector_health_check_error=?error);
+                (
+                    api_models::admin::ConnectorHealthStatus::Unhealthy,
+                    Some(error.current_context().to_string()),
+                )
+            }
+        };
+
+    api_models::admin::MerchantConnectorHealthCheckResponse {
+        merchant_connector_id: mca.merchant_connector_id,
+        connector_name: mca.connector_name,
+        status,
+        error_message,
+    }
+}
+
+/// Run the health check supported by the connector, `None` if the connector does not support one
+async fn run_payment_connector_health_check(
+    state: &AppState,
+    merchant_id: &str,
+    mca: &domain::MerchantConnectorAccount,
+) -> RouterResult<Option<Result<(), types::ErrorResponse>>> {
+    let connector_data = api::ConnectorData::get_connector_by_name(
+        &state.conf.connectors,
+        &mca.connector_name,
+        api::GetToken::Connector,
+    )?;
+
+    let health_check_result = match connector_data.connector.health_check() {
+        api::ConnectorHealthCheck::AccessToken => {
+            let auth_type: types::ConnectorAuthType = mca
+                .connector_account_details
+                .peek()
+                .clone()
+                .parse_value("ConnectorAuthType")
+                .change_context(errors::ApiErrorResponse::InternalServerError)
+                .attach_printable("Failed while parsing value for ConnectorAuthType")?;
+            Some(
+                payments::access_token::check_connector_credentials(
+                    state,
+                    &connector_data,
+                    merchant_id,
+                    auth_type,
+                    mca.metadata.clone(),
+                )
+                .await?,
+            )
+        }
+        api::ConnectorHealthCheck::NotSupported => None,
+    };
+
+    Ok(health_check_result)
+}
+
 pub async fn update_payment_connector(
     db: &dyn StorageInterface,
+    connectors: &settings::Connectors,
     merchant_id: &str,
     merchant_connector_id: &str,
     req: api_models::admin::MerchantConnectorUpdate,
@@ -668,6 +816,19 @@ pub async fn update_payment_connector(
         None => None,
     };
 
+    // Validate the updated merchant api details and return error if not in correct format
+    if let Some(connector_account_details) = req.connector_account_details.as_ref() {
+        let auth_type: types::ConnectorAuthType = connector_account_details
+            .clone()
+            .parse_value("ConnectorAuthType")
+            .change_context(errors::ApiErrorResponse::InvalidDataFormat {
+                field_name: "connector_account_details".to_string(),
+                expected_format: "auth_type and api_key".to_string(),
+            })?;
+        validate_connector_auth_type(connectors, &mca.connector_name, &auth_type)?;
+    }
+
+    let is_credentials_updated = req.connector_account_details.is_some();
     let payment_connector = storage::MerchantConnectorAccountUpdate::Update {
         merchant_id: None,
         connector_type: Some(req.connector_type.foreign_into()),
@@ -696,6 +857,15 @@ pub async fn update_payment_connector(
             format!("Failed while updating MerchantConnectorAccount: id: {merchant_connector_id}")
         })?;
 
+    // Calls to the connector fail without reaching it after it rejected the credentials, the
+    // updated credentials have to be tried again
+    if is_credentials_updated {
+        let _ = db
+            .delete_invalid_credentials_marker(merchant_id, merchant_connector_id)
+            .await
+            .map_err(|error| logger::error!(invalid_credentials_marker_error=?error));
+    }
+
     let response = updated_mca.try_into()?;
 
     Ok(service_api::ApplicationResponse::Json(response))
@@ -201,6 +201,8 @@ pub enum ApiClientError {
     RequestNotSent(String),
     #[error("Failed to decode response")]
     ResponseDecodingFailed,
+    #[error("Response exceeded the maximum allowed size")]
+    ResponseTooLarge,
 
     #[error("Server responded with Request Timeout")]
     RequestTimeoutReceived,
@@ -231,6 +233,14 @@ pub enum ConnectorError {
     ResponseDeserializationFailed,
     #[error("Failed to execute a processing step: {0:?}")]
     ProcessingStepFailed(Option<bytes::Bytes>),
+    #[error("Connector did not respond within the request timeout")]
+    RequestTimeout,
+    #[error("Connector response exceeded the maximum allowed size")]
+    ResponseTooLarge,
+    #[error("Connector rate limited the request, retry after {retry_after:?}")]
+    RateLimited {
+        retry_after: Option<std::time::Duration>,
+    },
     #[error("The connector returned an unexpected response: {0:?}")]
     UnexpectedResponseError(bytes::Bytes),
     #[error("Failed to parse custom routing rules from merchant account")]
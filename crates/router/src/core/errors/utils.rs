@@ -58,6 +58,9 @@ impl<T> ConnectorErrorExt<T> for error_stack::Result<T, errors::ConnectorError>
     fn to_refund_failed_response(self) -> error_stack::Result<T, errors::ApiErrorResponse> {
         self.map_err(|err| {
             let data = match err.current_context() {
+                errors::ConnectorError::RateLimited { .. } => {
+                    return err.change_context(errors::ApiErrorResponse::ConnectorRateLimited)
+                }
                 errors::ConnectorError::ProcessingStepFailed(Some(bytes)) => {
                     let response_str = std::str::from_utf8(bytes);
                     match response_str {
@@ -124,6 +127,9 @@ impl<T> ConnectorErrorExt<T> for error_stack::Result<T, errors::ConnectorError>
                 errors::ConnectorError::InvalidDataFormat { field_name } => {
                     errors::ApiErrorResponse::InvalidDataValue { field_name }
                 },
+                errors::ConnectorError::RateLimited { .. } => {
+                    errors::ApiErrorResponse::ConnectorRateLimited
+                },
                 _ => errors::ApiErrorResponse::InternalServerError,
             };
             err.change_context(error)
@@ -154,6 +160,9 @@ impl<T> ConnectorErrorExt<T> for error_stack::Result<T, errors::ConnectorError>
                 errors::ConnectorError::MissingRequiredField { field_name } => {
                     errors::ApiErrorResponse::MissingRequiredField { field_name }
                 }
+                errors::ConnectorError::RateLimited { .. } => {
+                    errors::ApiErrorResponse::ConnectorRateLimited
+                }
                 _ => {
                     logger::error!(%error,"Verify flow failed");
                     errors::ApiErrorResponse::PaymentAuthorizationFailed { data: None }
@@ -189,6 +198,9 @@ impl<T> ConnectorErrorExt<T> for error_stack::Result<T, errors::ConnectorError>
                         field_names: field_names.to_vec(),
                     }
                 }
+                errors::ConnectorError::RateLimited { .. } => {
+                    errors::ApiErrorResponse::ConnectorRateLimited
+                }
                 _ => errors::ApiErrorResponse::InternalServerError,
             };
             err.change_context(error)
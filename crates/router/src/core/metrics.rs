@@ -4,6 +4,7 @@ metrics_context!(CONTEXT);
 global_meter!(GLOBAL_METER, "ROUTER_API");
 
 counter_metric!(INCOMING_DISPUTE_WEBHOOK_METRIC, GLOBAL_METER); // No. of incoming dispute webhooks
+counter_metric!(INCOMING_WEBHOOK_SKIPPED_METRIC, GLOBAL_METER); // No. of incoming webhooks skipped as duplicate or out of order
 counter_metric!(
     INCOMING_DISPUTE_WEBHOOK_SIGNATURE_FAILURE_METRIC,
     GLOBAL_METER
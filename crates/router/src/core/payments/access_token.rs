@@ -1,16 +1,22 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    time::{Duration, Instant},
+};
 
-use common_utils::ext_traits::AsyncExt;
 use error_stack::{IntoReport, ResultExt};
+use masking::PeekInterface;
 
 use crate::{
+    consts,
     core::{
-        errors::{self, RouterResult},
+        errors::{self, CustomResult, RouterResult},
         payments,
     },
+    logger,
     routes::{metrics, AppState},
     services,
     types::{self, api as api_types, domain, transformers::ForeignInto},
+    utils,
 };
 
 /// After we get the access token, check if there was an error and if the flow should proceed further
@@ -47,6 +53,80 @@ pub fn update_router_data_with_access_token_result<F, Req, Res>(
     }
 }
 
+/// Check if the access token has to be created again, `skew_seconds` before it actually expires
+/// Returns bool
+/// true - The access token has expired or is about to expire, or its creation time is unknown
+/// false - The access token is still valid
+pub fn is_new_access_token_required(
+    access_token: &types::AccessToken,
+    current_time: i64,
+    skew_seconds: i64,
+) -> bool {
+    access_token
+        .access_token_created_at
+        .map(|created_at| {
+            created_at
+                .saturating_add(access_token.expires)
+                .saturating_sub(skew_seconds)
+                <= current_time
+        })
+        .unwrap_or(true)
+}
+
+/// Events in the lifecycle of a connector access token, logged to reconstruct the access token
+/// behaviour of a merchant connector account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+enum AccessTokenLifecycleEvent {
+    /// A new access token was received from the connector
+    Fetched,
+    /// The access token was stored for reuse by later requests
+    Cached,
+    /// A valid stored access token was used
+    Reused,
+    /// The stored access token has expired
+    Expired,
+    /// The connector rejected the stored access token before its expiry
+    Rejected,
+    /// An expired or rejected access token was replaced with a new one
+    Refreshed,
+    /// The connector did not return an access token
+    RefreshFailed,
+}
+
+/// Log an access token lifecycle event, the access token itself must never be logged
+fn log_access_token_event(
+    event: AccessTokenLifecycleEvent,
+    merchant_id: &str,
+    connector: &api_types::ConnectorData,
+) {
+    logger::info!(
+        access_token_event = %event,
+        merchant_id,
+        connector = %connector.connector_name,
+        "Access token lifecycle event"
+    );
+}
+
+/// The lifecycle events of an access token creation, in the order in which they happened
+fn get_access_token_creation_events(
+    access_token_result: &Result<types::AccessToken, types::ErrorResponse>,
+    has_expired_access_token: bool,
+    is_cached: bool,
+) -> Vec<AccessTokenLifecycleEvent> {
+    match access_token_result {
+        Ok(_) => [
+            Some(AccessTokenLifecycleEvent::Fetched),
+            is_cached.then_some(AccessTokenLifecycleEvent::Cached),
+            has_expired_access_token.then_some(AccessTokenLifecycleEvent::Refreshed),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+        Err(_) => vec![AccessTokenLifecycleEvent::RefreshFailed],
+    }
+}
+
 pub async fn add_access_token<
     F: Clone + 'static,
     Req: Debug + Clone + 'static,
@@ -62,63 +142,66 @@ pub async fn add_access_token<
         .supports_access_token(router_data.payment_method.foreign_into())
     {
         let merchant_id = &merchant_account.merchant_id;
-        let store = &*state.store;
-        let old_access_token = store
-            .get_access_token(merchant_id, connector.connector.id())
-            .await
-            .change_context(errors::ApiErrorResponse::InternalServerError)
-            .attach_printable("DB error when accessing the access token")?;
+        let current_time = state.clock.now_unix_timestamp();
+        let stored_access_token = get_stored_access_token(state, merchant_id, connector).await?;
+        let has_expired_access_token = stored_access_token
+            .as_ref()
+            .map(|access_token| {
+                is_new_access_token_required(
+                    access_token,
+                    current_time,
+                    state.conf.access_token.refresh_skew_secs,
+                )
+            })
+            .unwrap_or(false);
+        if has_expired_access_token {
+            log_access_token_event(AccessTokenLifecycleEvent::Expired, merchant_id, connector);
+        }
+        let old_access_token = stored_access_token.filter(|_| !has_expired_access_token);
 
         let res = match old_access_token {
-            Some(access_token) => Ok(Some(access_token)),
+            Some(access_token) => {
+                log_access_token_event(AccessTokenLifecycleEvent::Reused, merchant_id, connector);
+                metrics::ACCESS_TOKEN_CACHE_HIT.add(
+                    &metrics::CONTEXT,
+                    1,
+                    &[metrics::request::add_attributes(
+                        "connector",
+                        connector.connector_name.to_string(),
+                    )],
+                );
+                Ok(Some(
+                    warm_access_token(
+                        state,
+                        connector,
+                        merchant_id,
+                        router_data,
+                        access_token,
+                        current_time,
+                    )
+                    .await,
+                ))
+            }
             None => {
-                let cloned_router_data = router_data.clone();
-                let refresh_token_request_data = types::AccessTokenRequestData::try_from(
-                    router_data.connector_auth_type.clone(),
-                )
-                .into_report()
-                .attach_printable(
-                    "Could not create access token request, invalid connector account credentials",
-                )?;
-
-                let refresh_token_response_data: Result<types::AccessToken, types::ErrorResponse> =
-                    Err(types::ErrorResponse::default());
-                let refresh_token_router_data = payments::helpers::router_data_type_conversion::<
-                    _,
-                    api_types::AccessTokenAuth,
-                    _,
-                    _,
-                    _,
-                    _,
-                >(
-                    cloned_router_data,
-                    refresh_token_request_data,
-                    refresh_token_response_data,
+                // Covers both the access token not being present and the access token having expired
+                metrics::ACCESS_TOKEN_CACHE_MISS.add(
+                    &metrics::CONTEXT,
+                    1,
+                    &[metrics::request::add_attributes(
+                        "connector",
+                        connector.connector_name.to_string(),
+                    )],
                 );
-                refresh_connector_auth(
+
+                refresh_access_token_with_lock(
                     state,
                     connector,
-                    merchant_account,
-                    &refresh_token_router_data,
+                    merchant_id,
+                    router_data,
+                    has_expired_access_token,
+                    None,
                 )
                 .await?
-                .async_map(|access_token| async {
-                    //Store the access token in db
-                    let store = &*state.store;
-                    // This error should not be propagated, we don't want payments to fail once we have
-                    // the access token, the next request will create new access token
-                    let _ = store
-                        .set_access_token(
-                            merchant_id,
-                            connector.connector.id(),
-                            access_token.clone(),
-                        )
-                        .await
-                        .change_context(errors::ApiErrorResponse::InternalServerError)
-                        .attach_printable("DB error when setting the access token");
-                    Some(access_token)
-                })
-                .await
             }
         };
 
@@ -134,10 +217,404 @@ pub async fn add_access_token<
     }
 }
 
+/// Whether a new access token has to be fetched ahead of demand for the warm pool of the connector,
+/// `refresh_ahead_secs` before the access token has to be refreshed
+fn is_warm_refresh_required(
+    state: &AppState,
+    connector: &api_types::ConnectorData,
+    access_token: &types::AccessToken,
+    current_time: i64,
+) -> bool {
+    state
+        .conf
+        .access_token
+        .warm_pool
+        .get(connector.connector.id())
+        .map(|warm_pool| {
+            is_new_access_token_required(
+                access_token,
+                current_time,
+                state
+                    .conf
+                    .access_token
+                    .refresh_skew_secs
+                    .saturating_add(warm_pool.refresh_ahead_secs),
+            )
+        })
+        .unwrap_or(false)
+}
+
+/// Keep the access tokens of connectors with a warm pool valid ahead of demand. Once the stored
+/// access token enters the refresh ahead window, the request acquiring the access token lock
+/// fetches a new access token, while the other requests keep using the stored access token, which
+/// is still valid, instead of waiting for the refresh. Bursts of requests are then never serialized
+/// on a single refresh. Returns the access token to be used for the request.
+async fn warm_access_token<
+    F: Clone + 'static,
+    Req: Debug + Clone + 'static,
+    Res: Debug + Clone + 'static,
+>(
+    state: &AppState,
+    connector: &api_types::ConnectorData,
+    merchant_id: &str,
+    router_data: &types::RouterData<F, Req, Res>,
+    access_token: types::AccessToken,
+    current_time: i64,
+) -> types::AccessToken {
+    if !is_warm_refresh_required(state, connector, &access_token, current_time) {
+        return access_token;
+    }
+
+    let lock_value = utils::generate_id(consts::ID_LENGTH, "access_token_lock");
+    let is_lock_acquired = state
+        .store
+        .acquire_access_token_lock(
+            merchant_id,
+            connector.connector.id(),
+            &lock_value,
+            state.conf.access_token.lock_ttl_secs,
+        )
+        .await
+        .map_err(|error| logger::error!(access_token_lock_error=?error))
+        .unwrap_or(false);
+    if !is_lock_acquired {
+        return access_token;
+    }
+
+    // Another request may have fetched the new access token before the lock was acquired
+    let stored_access_token = get_stored_access_token(state, merchant_id, connector)
+        .await
+        .ok()
+        .flatten()
+        .filter(|stored_access_token| {
+            !is_warm_refresh_required(state, connector, stored_access_token, current_time)
+        });
+    let warmed_access_token = match stored_access_token {
+        Some(stored_access_token) => Some(stored_access_token),
+        None => {
+            match create_access_token(state, connector, merchant_id, router_data, false).await {
+                Ok(Ok(warmed_access_token)) => warmed_access_token,
+                Ok(Err(_)) | Err(_) => {
+                    logger::warn!(
+                        connector = connector.connector_name.to_string(),
+                        "Access token could not be fetched ahead of demand"
+                    );
+                    None
+                }
+            }
+        }
+    };
+
+    let _ = state
+        .store
+        .release_access_token_lock(merchant_id, connector.connector.id(), &lock_value)
+        .await
+        .map_err(|error| logger::error!(access_token_lock_error=?error));
+
+    warmed_access_token.unwrap_or(access_token)
+}
+
+/// Get the access token of the connector from the store, whether or not it has expired
+async fn get_stored_access_token(
+    state: &AppState,
+    merchant_id: &str,
+    connector: &api_types::ConnectorData,
+) -> RouterResult<Option<types::AccessToken>> {
+    state
+        .store
+        .get_access_token(merchant_id, connector.connector.id())
+        .await
+        .change_context(errors::ApiErrorResponse::InternalServerError)
+        .attach_printable("DB error when accessing the access token")
+}
+
+/// Get the access token of the connector from the store, if it is still valid and it is not the
+/// access token rejected by the connector
+async fn get_valid_access_token(
+    state: &AppState,
+    merchant_id: &str,
+    connector: &api_types::ConnectorData,
+    rejected_access_token: Option<&types::AccessToken>,
+) -> RouterResult<Option<types::AccessToken>> {
+    let access_token = get_stored_access_token(state, merchant_id, connector).await?;
+    let current_time = state.clock.now_unix_timestamp();
+
+    Ok(access_token.filter(|access_token| {
+        !is_new_access_token_required(
+            access_token,
+            current_time,
+            state.conf.access_token.refresh_skew_secs,
+        ) && rejected_access_token.map_or(true, |rejected_access_token| {
+            rejected_access_token.token.peek() != access_token.token.peek()
+        })
+    }))
+}
+
+/// Wait for the request holding the access token lock to store the new access token
+async fn wait_for_access_token(
+    state: &AppState,
+    merchant_id: &str,
+    connector: &api_types::ConnectorData,
+    rejected_access_token: Option<&types::AccessToken>,
+) -> RouterResult<Option<types::AccessToken>> {
+    let access_token_config = &state.conf.access_token;
+    let wait_till =
+        Instant::now() + Duration::from_millis(access_token_config.lock_wait_timeout_ms);
+
+    while Instant::now() < wait_till {
+        tokio::time::sleep(Duration::from_millis(
+            access_token_config.lock_poll_interval_ms,
+        ))
+        .await;
+        if let Some(access_token) =
+            get_valid_access_token(state, merchant_id, connector, rejected_access_token).await?
+        {
+            return Ok(Some(access_token));
+        }
+    }
+
+    logger::warn!(
+        connector = connector.connector_name.to_string(),
+        "Access token was not refreshed within the lock wait timeout"
+    );
+    Ok(None)
+}
+
+/// Create a new access token while holding the access token lock of the connector, so that only
+/// one request refreshes the access token at a time and the others wait for it to be stored and
+/// reuse it. If the lock cannot be acquired or the access token is not stored within the wait
+/// timeout, the access token is created without the lock so that the payment is never blocked.
+/// The access token rejected by the connector, if any, is evicted and never reused.
+async fn refresh_access_token_with_lock<
+    F: Clone + 'static,
+    Req: Debug + Clone + 'static,
+    Res: Debug + Clone + 'static,
+>(
+    state: &AppState,
+    connector: &api_types::ConnectorData,
+    merchant_id: &str,
+    router_data: &types::RouterData<F, Req, Res>,
+    has_expired_access_token: bool,
+    rejected_access_token: Option<&types::AccessToken>,
+) -> RouterResult<Result<Option<types::AccessToken>, types::ErrorResponse>> {
+    let lock_value = utils::generate_id(consts::ID_LENGTH, "access_token_lock");
+    let is_lock_acquired = state
+        .store
+        .acquire_access_token_lock(
+            merchant_id,
+            connector.connector.id(),
+            &lock_value,
+            state.conf.access_token.lock_ttl_secs,
+        )
+        .await
+        .map_err(|error| logger::error!(access_token_lock_error=?error))
+        .unwrap_or(false);
+
+    let refreshed_access_token = match (is_lock_acquired, rejected_access_token) {
+        (true, None) => Ok(None),
+        // Another request may have replaced the rejected access token before the lock was acquired
+        (true, Some(rejected_access_token)) => {
+            get_valid_access_token(state, merchant_id, connector, Some(rejected_access_token)).await
+        }
+        (false, _) => {
+            wait_for_access_token(state, merchant_id, connector, rejected_access_token).await
+        }
+    };
+
+    let res = match refreshed_access_token {
+        Ok(Some(access_token)) => {
+            log_access_token_event(AccessTokenLifecycleEvent::Reused, merchant_id, connector);
+            Ok(Ok(Some(access_token)))
+        }
+        Ok(None) => {
+            if rejected_access_token.is_some() {
+                let _ = state
+                    .store
+                    .delete_access_token(merchant_id, connector.connector.id())
+                    .await
+                    .map_err(|error| logger::error!(access_token_delete_error=?error));
+            }
+            create_access_token(
+                state,
+                connector,
+                merchant_id,
+                router_data,
+                has_expired_access_token,
+            )
+            .await
+        }
+        Err(error) => Err(error),
+    };
+
+    if is_lock_acquired {
+        let _ = state
+            .store
+            .release_access_token_lock(merchant_id, connector.connector.id(), &lock_value)
+            .await
+            .map_err(|error| logger::error!(access_token_lock_error=?error));
+    }
+
+    res
+}
+
+async fn create_access_token<
+    F: Clone + 'static,
+    Req: Debug + Clone + 'static,
+    Res: Debug + Clone + 'static,
+>(
+    state: &AppState,
+    connector: &api_types::ConnectorData,
+    merchant_id: &str,
+    router_data: &types::RouterData<F, Req, Res>,
+    has_expired_access_token: bool,
+) -> RouterResult<Result<Option<types::AccessToken>, types::ErrorResponse>> {
+    if services::api::invalid_credentials::is_marked(state, router_data).await {
+        return Ok(Err(services::api::invalid_credentials::get_error_response()));
+    }
+
+    let current_time = state.clock.now_unix_timestamp();
+    let cloned_router_data = router_data.clone();
+    let access_token_metadata = get_access_token_metadata(&router_data.connector_meta_data);
+    let refresh_token_request_data = types::AccessTokenRequestData {
+        scopes: access_token_metadata.access_token_scopes,
+        grant_type: access_token_metadata.access_token_grant_type,
+        ..types::AccessTokenRequestData::try_from(router_data.connector_auth_type.clone())
+            .into_report()
+            .attach_printable(
+                "Could not create access token request, invalid connector account credentials",
+            )?
+    };
+
+    let refresh_token_response_data: Result<types::AccessToken, types::ErrorResponse> =
+        Err(types::ErrorResponse::default());
+    let refresh_token_router_data = payments::helpers::router_data_type_conversion::<
+        _,
+        api_types::AccessTokenAuth,
+        _,
+        _,
+        _,
+        _,
+    >(
+        cloned_router_data,
+        refresh_token_request_data,
+        refresh_token_response_data,
+    );
+    let access_token_result =
+        match refresh_connector_auth(state, connector, &refresh_token_router_data).await {
+            Ok(access_token_result) => access_token_result,
+            Err(error) => {
+                log_access_token_event(
+                    AccessTokenLifecycleEvent::RefreshFailed,
+                    merchant_id,
+                    connector,
+                );
+                return Err(error);
+            }
+        }
+        .map(|access_token| types::AccessToken {
+            access_token_created_at: Some(current_time),
+            ..access_token
+        });
+
+    if let Err(error_response) = &access_token_result {
+        services::api::invalid_credentials::mark_if_rejected(state, router_data, error_response)
+            .await;
+    }
+
+    let (access_token_result, is_cached) = match access_token_result {
+        //Store the access token in db, unless a concurrent refresh stored a newer access token
+        // This error should not be propagated, we don't want payments to fail once we have
+        // the access token, the next request will create new access token
+        Ok(access_token) => match state
+            .store
+            .compare_and_set_access_token(
+                merchant_id,
+                connector.connector.id(),
+                access_token.clone(),
+            )
+            .await
+        {
+            Ok(stored_access_token) => (Ok(stored_access_token), true),
+            Err(error) => {
+                logger::error!(access_token_store_error=?error);
+                (Ok(access_token), false)
+            }
+        },
+        Err(error_response) => (Err(error_response), false),
+    };
+
+    get_access_token_creation_events(&access_token_result, has_expired_access_token, is_cached)
+        .into_iter()
+        .for_each(|event| log_access_token_event(event, merchant_id, connector));
+
+    Ok(access_token_result.map(Some))
+}
+
+/// Access token options configured in the metadata of the merchant connector account
+#[derive(Debug, Default, serde::Deserialize)]
+struct AccessTokenMetadata {
+    #[serde(default)]
+    access_token_scopes: Vec<String>,
+    access_token_grant_type: Option<String>,
+}
+
+/// Metadata which is missing or does not contain valid access token options is ignored, so that
+/// connectors fall back to their default scopes and grant type
+fn get_access_token_metadata(
+    connector_meta_data: &Option<common_utils::pii::SecretSerdeValue>,
+) -> AccessTokenMetadata {
+    connector_meta_data
+        .as_ref()
+        .and_then(|metadata| serde_json::from_value(metadata.peek().clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Evict the access token rejected by the connector and create a new one under the access token
+/// lock, so that the rejected request can be retried with it. Returns `None` if a new access token
+/// could not be created.
+pub async fn refresh_rejected_access_token<
+    F: Clone + 'static,
+    Req: Debug + Clone + 'static,
+    Res: Debug + Clone + 'static,
+>(
+    state: &AppState,
+    router_data: &types::RouterData<F, Req, Res>,
+) -> Option<types::AccessToken> {
+    let merchant_id = &router_data.merchant_id;
+    let connector = api_types::ConnectorData::get_connector_by_name(
+        &state.conf.connectors,
+        &router_data.connector,
+        api_types::GetToken::Connector,
+    )
+    .map_err(|error| logger::error!(access_token_refresh_error=?error))
+    .ok()?;
+    log_access_token_event(AccessTokenLifecycleEvent::Rejected, merchant_id, &connector);
+
+    match refresh_access_token_with_lock(
+        state,
+        &connector,
+        merchant_id,
+        router_data,
+        true,
+        router_data.access_token.as_ref(),
+    )
+    .await
+    {
+        Ok(Ok(access_token)) => access_token,
+        Ok(Err(error_response)) => {
+            logger::warn!(access_token_error_response=?error_response);
+            None
+        }
+        Err(error) => {
+            logger::error!(access_token_refresh_error=?error);
+            None
+        }
+    }
+}
+
 pub async fn refresh_connector_auth(
     state: &AppState,
     connector: &api_types::ConnectorData,
-    _merchant_account: &domain::MerchantAccount,
     router_data: &types::RouterData<
         api_types::AccessTokenAuth,
         types::AccessTokenRequestData,
@@ -151,23 +628,762 @@ pub async fn refresh_connector_auth(
         types::AccessToken,
     > = connector.connector.get_connector_integration();
 
-    let access_token_router_data = services::execute_connector_processing_step(
-        state,
-        connector_integration,
-        router_data,
-        payments::CallConnectorAction::Trigger,
-        None,
-    )
-    .await
+    let retry_config = &state.conf.access_token;
+    let mut attempt = 1;
+    let access_token_router_data = loop {
+        let access_token_router_data = services::execute_connector_call(
+            state,
+            connector_integration.clone(),
+            router_data,
+            payments::CallConnectorAction::Trigger,
+            None,
+        )
+        .await;
+
+        if attempt >= retry_config.max_refresh_attempts
+            || !is_retryable_access_token_error(&access_token_router_data)
+        {
+            break access_token_router_data;
+        }
+
+        let backoff = match get_rate_limit_retry_after(&access_token_router_data) {
+            Some(retry_after)
+                if retry_after > Duration::from_millis(retry_config.max_retry_after_ms) =>
+            {
+                logger::warn!(
+                    connector = connector.connector_name.to_string(),
+                    ?retry_after,
+                    "Not retrying access token creation, the connector asked for a longer delay"
+                );
+                break access_token_router_data;
+            }
+            Some(retry_after) => retry_after,
+            None => Duration::from_millis(
+                retry_config
+                    .refresh_backoff_base_ms
+                    .saturating_mul(2_u64.saturating_pow(attempt - 1)),
+            ),
+        };
+        logger::warn!(
+            connector = connector.connector_name.to_string(),
+            attempt,
+            ?backoff,
+            "Retrying access token creation"
+        );
+        tokio::time::sleep(backoff).await;
+        attempt += 1;
+    }
     .change_context(errors::ApiErrorResponse::InternalServerError)
     .attach_printable("Could not refresh access token")?;
     metrics::ACCESS_TOKEN_CREATION.add(
         &metrics::CONTEXT,
         1,
-        &[metrics::request::add_attributes(
-            "connector",
-            connector.connector_name.to_string(),
-        )],
+        &[
+            metrics::request::add_attributes("connector", connector.connector_name.to_string()),
+            metrics::request::add_attributes("attempts", i64::from(attempt)),
+        ],
     );
     Ok(access_token_router_data.response)
 }
+
+/// Verify the credentials of a merchant connector account by creating an access token. The access
+/// token is not stored, so that the check does not replace the access token used for payments.
+pub async fn check_connector_credentials(
+    state: &AppState,
+    connector: &api_types::ConnectorData,
+    merchant_id: &str,
+    connector_auth_type: types::ConnectorAuthType,
+    connector_meta_data: Option<common_utils::pii::SecretSerdeValue>,
+) -> RouterResult<Result<(), types::ErrorResponse>> {
+    let access_token_metadata = get_access_token_metadata(&connector_meta_data);
+    let request = types::AccessTokenRequestData {
+        scopes: access_token_metadata.access_token_scopes,
+        grant_type: access_token_metadata.access_token_grant_type,
+        ..types::AccessTokenRequestData::try_from(connector_auth_type.clone())
+            .into_report()
+            .change_context(errors::ApiErrorResponse::InvalidDataValue {
+                field_name: "connector_account_details",
+            })
+            .attach_printable(
+                "Could not create access token request, invalid connector account credentials",
+            )?
+    };
+    let router_data = types::RefreshTokenRouterData {
+        flow: std::marker::PhantomData,
+        merchant_id: merchant_id.to_string(),
+        customer_id: None,
+        connector_customer: None,
+        connector: connector.connector_name.to_string(),
+        payment_id: String::new(),
+        attempt_id: String::new(),
+        status: Default::default(),
+        payment_method: Default::default(),
+        connector_auth_type,
+        description: None,
+        return_url: None,
+        address: Default::default(),
+        auth_type: Default::default(),
+        connector_meta_data,
+        amount_captured: None,
+        access_token: None,
+        session_token: None,
+        reference_id: None,
+        payment_method_token: None,
+        preprocessing_id: None,
+        test_mode: None,
+        merchant_connector_id: None,
+        correlation_metadata: Default::default(),
+        request,
+        response: Err(types::ErrorResponse::default()),
+        payment_method_id: None,
+    };
+
+    Ok(refresh_connector_auth(state, connector, &router_data)
+        .await?
+        .map(|_access_token| ()))
+}
+
+/// Timeouts, network failures, rate limiting and server errors from the connector are transient,
+/// authentication rejections (401 / 403) are not and should not be retried
+fn is_retryable_access_token_error(
+    access_token_router_data: &CustomResult<types::RefreshTokenRouterData, errors::ConnectorError>,
+) -> bool {
+    match access_token_router_data {
+        Ok(router_data) => router_data
+            .response
+            .as_ref()
+            .err()
+            .map(|error_response| matches!(error_response.status_code, 408 | 429 | 500..=599))
+            .unwrap_or(false),
+        Err(error) => matches!(
+            error.current_context(),
+            errors::ConnectorError::ProcessingStepFailed(None)
+                | errors::ConnectorError::RequestTimeout
+                | errors::ConnectorError::RateLimited { .. }
+        ),
+    }
+}
+
+/// Delay requested by the connector when it rate limited the access token request
+fn get_rate_limit_retry_after(
+    access_token_router_data: &CustomResult<types::RefreshTokenRouterData, errors::ConnectorError>,
+) -> Option<Duration> {
+    match access_token_router_data {
+        Err(error) => match error.current_context() {
+            errors::ConnectorError::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        },
+        Ok(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_access_token(expires: i64, access_token_created_at: Option<i64>) -> types::AccessToken {
+        types::AccessToken {
+            token: masking::Secret::new("access_token".to_string()),
+            expires,
+            access_token_created_at,
+        }
+    }
+
+    fn get_router_data() -> types::RouterData<api_types::AccessTokenAuth, (), ()> {
+        types::RouterData {
+            flow: std::marker::PhantomData,
+            merchant_id: "merchant_1".to_string(),
+            customer_id: None,
+            connector_customer: None,
+            connector: "airwallex".to_string(),
+            payment_id: "payment_1".to_string(),
+            attempt_id: "attempt_1".to_string(),
+            status: Default::default(),
+            payment_method: Default::default(),
+            connector_auth_type: Default::default(),
+            description: None,
+            return_url: None,
+            address: Default::default(),
+            auth_type: Default::default(),
+            connector_meta_data: None,
+            amount_captured: None,
+            access_token: None,
+            session_token: None,
+            reference_id: None,
+            payment_method_token: None,
+            preprocessing_id: None,
+            test_mode: None,
+            merchant_connector_id: None,
+            correlation_metadata: Default::default(),
+            request: (),
+            response: Ok(()),
+            payment_method_id: None,
+        }
+    }
+
+    fn get_add_access_token_result(
+        access_token_result: Result<Option<types::AccessToken>, types::ErrorResponse>,
+        connector_supports_access_token: bool,
+    ) -> types::AddAccessTokenResult {
+        types::AddAccessTokenResult {
+            access_token_result,
+            connector_supports_access_token,
+        }
+    }
+
+    #[test]
+    fn test_access_token_result_sets_access_token() {
+        let mut router_data = get_router_data();
+        let add_access_token_result =
+            get_add_access_token_result(Ok(Some(get_access_token(3600, Some(1_000)))), true);
+
+        assert!(update_router_data_with_access_token_result(
+            &add_access_token_result,
+            &mut router_data,
+            &payments::CallConnectorAction::Trigger,
+        ));
+        assert_eq!(
+            router_data
+                .access_token
+                .map(|access_token| access_token.token.peek().clone()),
+            Some("access_token".to_string())
+        );
+        assert!(router_data.response.is_ok());
+    }
+
+    #[test]
+    fn test_access_token_result_error_sets_error_response() {
+        let mut router_data = get_router_data();
+        let error_response = types::ErrorResponse {
+            status_code: 401,
+            ..Default::default()
+        };
+        let add_access_token_result = get_add_access_token_result(Err(error_response), true);
+
+        assert!(!update_router_data_with_access_token_result(
+            &add_access_token_result,
+            &mut router_data,
+            &payments::CallConnectorAction::Trigger,
+        ));
+        assert!(router_data.access_token.is_none());
+        assert_eq!(
+            router_data.response.map_err(|error| error.status_code),
+            Err(401)
+        );
+    }
+
+    #[test]
+    fn test_access_token_result_is_ignored_when_connector_is_not_called() {
+        let mut router_data = get_router_data();
+        let add_access_token_result =
+            get_add_access_token_result(Err(types::ErrorResponse::default()), true);
+
+        assert!(update_router_data_with_access_token_result(
+            &add_access_token_result,
+            &mut router_data,
+            &payments::CallConnectorAction::Avoid,
+        ));
+        assert!(router_data.access_token.is_none());
+        assert!(router_data.response.is_ok());
+    }
+
+    #[test]
+    fn test_access_token_result_is_ignored_when_connector_does_not_support_access_token() {
+        let mut router_data = get_router_data();
+        let add_access_token_result =
+            get_add_access_token_result(Ok(Some(get_access_token(3600, Some(1_000)))), false);
+
+        assert!(update_router_data_with_access_token_result(
+            &add_access_token_result,
+            &mut router_data,
+            &payments::CallConnectorAction::Trigger,
+        ));
+        assert!(router_data.access_token.is_none());
+        assert!(router_data.response.is_ok());
+    }
+
+    #[test]
+    fn test_access_token_within_lifetime_is_reused() {
+        let access_token = get_access_token(3600, Some(1_000));
+        assert!(!is_new_access_token_required(&access_token, 2_000, 0));
+    }
+
+    #[test]
+    fn test_access_token_past_lifetime_is_refreshed() {
+        let access_token = get_access_token(3600, Some(1_000));
+        assert!(is_new_access_token_required(&access_token, 4_600, 0));
+        assert!(is_new_access_token_required(&access_token, 10_000, 0));
+    }
+
+    #[test]
+    fn test_access_token_without_creation_time_is_refreshed() {
+        let access_token = get_access_token(3600, None);
+        assert!(is_new_access_token_required(&access_token, 2_000, 0));
+    }
+
+    #[test]
+    fn test_access_token_with_zero_lifetime_is_refreshed() {
+        let access_token = get_access_token(0, Some(1_000));
+        assert!(is_new_access_token_required(&access_token, 1_000, 0));
+    }
+
+    #[test]
+    fn test_access_token_within_refresh_skew_is_refreshed() {
+        // Expires in 30 seconds
+        let access_token = get_access_token(3600, Some(1_000));
+        assert!(is_new_access_token_required(&access_token, 4_570, 60));
+    }
+
+    #[test]
+    fn test_access_token_outside_refresh_skew_is_reused() {
+        // Expires in 30 seconds
+        let access_token = get_access_token(3600, Some(1_000));
+        assert!(!is_new_access_token_required(&access_token, 4_570, 10));
+    }
+
+    #[test]
+    fn test_access_token_expiry_boundaries() {
+        use crate::utils::clock::{Clock, MockClock};
+
+        // Expires at 4_600
+        let access_token = get_access_token(3600, Some(1_000));
+        let clock = MockClock::new(4_599);
+        assert!(!is_new_access_token_required(
+            &access_token,
+            clock.now_unix_timestamp(),
+            0
+        ));
+
+        clock.advance(1);
+        assert!(is_new_access_token_required(
+            &access_token,
+            clock.now_unix_timestamp(),
+            0
+        ));
+
+        clock.advance(1);
+        assert!(is_new_access_token_required(
+            &access_token,
+            clock.now_unix_timestamp(),
+            0
+        ));
+    }
+
+    #[test]
+    fn test_access_token_refresh_emits_fetched_and_cached_events() {
+        let access_token_result = Ok(get_access_token(3600, Some(1_000)));
+
+        assert_eq!(
+            get_access_token_creation_events(&access_token_result, false, true),
+            vec![
+                AccessTokenLifecycleEvent::Fetched,
+                AccessTokenLifecycleEvent::Cached
+            ]
+        );
+        assert_eq!(
+            get_access_token_creation_events(&access_token_result, true, true),
+            vec![
+                AccessTokenLifecycleEvent::Fetched,
+                AccessTokenLifecycleEvent::Cached,
+                AccessTokenLifecycleEvent::Refreshed
+            ]
+        );
+    }
+
+    #[test]
+    fn test_access_token_refresh_failure_emits_refresh_failed_event() {
+        let access_token_result = Err(types::ErrorResponse::default());
+
+        assert_eq!(
+            get_access_token_creation_events(&access_token_result, true, false),
+            vec![AccessTokenLifecycleEvent::RefreshFailed]
+        );
+    }
+
+    #[test]
+    fn test_access_token_debug_output_is_masked() {
+        let access_token = types::AccessToken {
+            token: masking::Secret::new("secret_token_1".to_string()),
+            ..get_access_token(3600, Some(1_000))
+        };
+        let debug_output = format!("{access_token:?}");
+
+        assert!(!debug_output.contains("secret_token_1"));
+        assert!(debug_output.contains("3600"));
+    }
+
+    #[test]
+    fn test_access_token_metadata_is_read_from_connector_metadata() {
+        let connector_meta_data = Some(masking::Secret::new(serde_json::json!({
+            "access_token_scopes": ["payment", "refund"],
+            "access_token_grant_type": "client_credentials",
+        })));
+        let access_token_metadata = get_access_token_metadata(&connector_meta_data);
+
+        assert_eq!(
+            access_token_metadata.access_token_scopes,
+            vec!["payment".to_string(), "refund".to_string()]
+        );
+        assert_eq!(
+            access_token_metadata.access_token_grant_type.as_deref(),
+            Some("client_credentials")
+        );
+    }
+
+    #[test]
+    fn test_access_token_metadata_defaults_when_absent() {
+        let connector_meta_data = Some(masking::Secret::new(serde_json::json!({
+            "city": "NY",
+        })));
+
+        for connector_meta_data in [None, connector_meta_data] {
+            let access_token_metadata = get_access_token_metadata(&connector_meta_data);
+            assert!(access_token_metadata.access_token_scopes.is_empty());
+            assert!(access_token_metadata.access_token_grant_type.is_none());
+        }
+    }
+
+    #[test]
+    fn test_rate_limited_access_token_request_is_retried_after_requested_delay() {
+        let access_token_router_data: CustomResult<types::RefreshTokenRouterData, _> =
+            Err(error_stack::report!(errors::ConnectorError::RateLimited {
+                retry_after: Some(Duration::from_secs(1)),
+            }));
+
+        assert!(is_retryable_access_token_error(&access_token_router_data));
+        assert_eq!(
+            get_rate_limit_retry_after(&access_token_router_data),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn test_failed_access_token_request_has_no_retry_after() {
+        let access_token_router_data: CustomResult<types::RefreshTokenRouterData, _> =
+            Err(error_stack::report!(errors::ConnectorError::RequestTimeout));
+
+        assert!(is_retryable_access_token_error(&access_token_router_data));
+        assert_eq!(get_rate_limit_retry_after(&access_token_router_data), None);
+    }
+
+    async fn get_mock_app_state(conf: crate::configs::settings::Settings) -> AppState {
+        AppState::with_storage(
+            conf,
+            crate::db::StorageImpl::Mock,
+            tokio::sync::oneshot::channel().0,
+        )
+        .await
+    }
+
+    fn get_merchant_account() -> domain::MerchantAccount {
+        domain::MerchantAccount {
+            id: None,
+            merchant_id: "merchant_1".to_string(),
+            return_url: None,
+            enable_payment_response_hash: false,
+            payment_response_hash_key: None,
+            redirect_to_merchant_with_http_post: false,
+            merchant_name: None,
+            merchant_details: None,
+            webhook_details: None,
+            sub_merchants_enabled: None,
+            parent_merchant_id: None,
+            publishable_key: None,
+            storage_scheme: types::storage::enums::MerchantStorageScheme::PostgresOnly,
+            locker_id: None,
+            metadata: None,
+            routing_algorithm: None,
+            primary_business_details: serde_json::json!([]),
+            frm_routing_algorithm: None,
+            created_at: common_utils::date_time::now(),
+            modified_at: common_utils::date_time::now(),
+            intent_fulfillment_time: None,
+        }
+    }
+
+    fn get_airwallex_connector_data(state: &AppState) -> api_types::ConnectorData {
+        api_types::ConnectorData::get_connector_by_name(
+            &state.conf.connectors,
+            "airwallex",
+            api_types::GetToken::Connector,
+        )
+        .unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn test_warm_pool_serves_concurrent_requests_without_refreshing() {
+        let mut conf = crate::configs::settings::Settings::default();
+        conf.access_token.warm_pool.insert(
+            "airwallex".to_string(),
+            crate::configs::settings::AccessTokenWarmPool {
+                refresh_ahead_secs: 300,
+            },
+        );
+        let state = get_mock_app_state(conf).await;
+        // Expires in 200 seconds, within the refresh ahead window but outside the refresh skew
+        let current_time = common_utils::date_time::now_unix_timestamp();
+        state
+            .store
+            .set_access_token(
+                "merchant_1",
+                "airwallex",
+                get_access_token(3600, Some(current_time - 3400)),
+            )
+            .await
+            .unwrap();
+        // A new access token is being fetched ahead of demand by another request
+        assert!(state
+            .store
+            .acquire_access_token_lock("merchant_1", "airwallex", "warming_request", 10)
+            .await
+            .unwrap());
+        let connector = get_airwallex_connector_data(&state);
+        let merchant_account = get_merchant_account();
+        let router_data = get_router_data();
+
+        let started_at = Instant::now();
+        let results = futures::future::join_all(
+            (0..10).map(|_| add_access_token(&state, &connector, &merchant_account, &router_data)),
+        )
+        .await;
+
+        // Refreshing the access token would have reached the connector, which is not configured
+        for result in results {
+            let access_token = result.unwrap().access_token_result.unwrap().unwrap();
+            assert_eq!(access_token.token.peek(), "access_token");
+            assert_eq!(
+                access_token.access_token_created_at,
+                Some(current_time - 3400)
+            );
+        }
+        assert!(
+            started_at.elapsed()
+                < Duration::from_millis(state.conf.access_token.lock_wait_timeout_ms)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_access_token_outside_warm_pool_window_is_not_refreshed_ahead() {
+        let mut conf = crate::configs::settings::Settings::default();
+        conf.access_token.warm_pool.insert(
+            "airwallex".to_string(),
+            crate::configs::settings::AccessTokenWarmPool {
+                refresh_ahead_secs: 300,
+            },
+        );
+        let state = get_mock_app_state(conf).await;
+        // Expires in 1000 seconds, outside the refresh ahead window
+        let current_time = common_utils::date_time::now_unix_timestamp();
+        let access_token = get_access_token(3600, Some(current_time - 2600));
+        let connector = get_airwallex_connector_data(&state);
+
+        assert!(!is_warm_refresh_required(
+            &state,
+            &connector,
+            &access_token,
+            current_time
+        ));
+        assert!(is_warm_refresh_required(
+            &state,
+            &connector,
+            &access_token,
+            current_time + 800
+        ));
+        // Connectors without a warm pool are only refreshed within the refresh skew
+        let state = get_mock_app_state(Default::default()).await;
+        assert!(!is_warm_refresh_required(
+            &state,
+            &connector,
+            &access_token,
+            current_time + 800
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn test_add_access_token_refreshes_from_expiry_of_app_state_clock() {
+        use crate::utils::clock::MockClock;
+
+        let mut conf = crate::configs::settings::Settings::default();
+        conf.access_token.refresh_skew_secs = 0;
+        let mut state = get_mock_app_state(conf).await;
+        let clock = MockClock::new(0);
+        state.clock = std::sync::Arc::new(clock.clone());
+        // Expires at 4_600
+        state
+            .store
+            .set_access_token(
+                "merchant_1",
+                "airwallex",
+                get_access_token(3600, Some(1_000)),
+            )
+            .await
+            .unwrap();
+        // Creating a new access token fails without reaching the connector while the credentials
+        // are marked invalid, which tells a refresh apart from a reuse of the stored access token
+        state
+            .store
+            .set_invalid_credentials_marker("merchant_1", "mca_1", 60)
+            .await
+            .unwrap();
+        let connector = get_airwallex_connector_data(&state);
+        let merchant_account = get_merchant_account();
+        let router_data = types::RouterData {
+            merchant_connector_id: Some("mca_1".to_string()),
+            ..get_router_data()
+        };
+
+        clock.set(4_599);
+        let access_token_result =
+            add_access_token(&state, &connector, &merchant_account, &router_data)
+                .await
+                .unwrap()
+                .access_token_result;
+        assert_eq!(
+            access_token_result
+                .unwrap()
+                .map(|access_token| access_token.access_token_created_at),
+            Some(Some(1_000))
+        );
+
+        for now in [4_600, 4_601] {
+            clock.set(now);
+            let access_token_result =
+                add_access_token(&state, &connector, &merchant_account, &router_data)
+                    .await
+                    .unwrap()
+                    .access_token_result;
+            assert_eq!(
+                access_token_result.unwrap_err().code,
+                consts::INVALID_CREDENTIALS_ERROR_CODE
+            );
+        }
+    }
+
+    /// Collects the fields of the access token lifecycle events logged while it is the default
+    /// subscriber
+    #[derive(Default, Clone)]
+    struct LifecycleEventCollector {
+        events: std::sync::Arc<std::sync::Mutex<Vec<std::collections::HashMap<String, String>>>>,
+    }
+
+    struct FieldCollector<'a>(&'a mut std::collections::HashMap<String, String>);
+
+    impl router_env::tracing::field::Visit for FieldCollector<'_> {
+        fn record_str(&mut self, field: &router_env::tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &router_env::tracing::field::Field, value: &dyn Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    impl router_env::tracing::Subscriber for LifecycleEventCollector {
+        fn enabled(&self, _metadata: &router_env::tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(
+            &self,
+            _span: &router_env::tracing::span::Attributes<'_>,
+        ) -> router_env::tracing::span::Id {
+            router_env::tracing::span::Id::from_u64(1)
+        }
+
+        fn record(
+            &self,
+            _span: &router_env::tracing::span::Id,
+            _values: &router_env::tracing::span::Record<'_>,
+        ) {
+        }
+
+        fn record_follows_from(
+            &self,
+            _span: &router_env::tracing::span::Id,
+            _follows: &router_env::tracing::span::Id,
+        ) {
+        }
+
+        fn event(&self, event: &router_env::tracing::Event<'_>) {
+            let mut fields = std::collections::HashMap::new();
+            event.record(&mut FieldCollector(&mut fields));
+            if fields.contains_key("access_token_event") {
+                self.events.lock().unwrap().push(fields);
+            }
+        }
+
+        fn enter(&self, _span: &router_env::tracing::span::Id) {}
+
+        fn exit(&self, _span: &router_env::tracing::span::Id) {}
+    }
+
+    /// Call `add_access_token` with the given stored access token, returning the lifecycle events
+    /// logged meanwhile
+    async fn get_logged_lifecycle_events(
+        stored_access_token: types::AccessToken,
+    ) -> Vec<std::collections::HashMap<String, String>> {
+        let state = get_mock_app_state(Default::default()).await;
+        state
+            .store
+            .set_access_token("merchant_1", "airwallex", stored_access_token)
+            .await
+            .unwrap();
+        // Creating a new access token fails without reaching the connector
+        state
+            .store
+            .set_invalid_credentials_marker("merchant_1", "mca_1", 60)
+            .await
+            .unwrap();
+        let router_data = types::RouterData {
+            merchant_connector_id: Some("mca_1".to_string()),
+            ..get_router_data()
+        };
+        let collector = LifecycleEventCollector::default();
+
+        let guard = router_env::tracing::subscriber::set_default(collector.clone());
+        add_access_token(
+            &state,
+            &get_airwallex_connector_data(&state),
+            &get_merchant_account(),
+            &router_data,
+        )
+        .await
+        .unwrap();
+        drop(guard);
+
+        let events = collector.events.lock().unwrap().clone();
+        events
+    }
+
+    #[actix_rt::test]
+    async fn test_reused_access_token_is_logged_without_the_access_token() {
+        let stored_access_token = types::AccessToken {
+            token: masking::Secret::new("secret_token_1".to_string()),
+            ..get_access_token(3600, Some(common_utils::date_time::now_unix_timestamp()))
+        };
+
+        let events = get_logged_lifecycle_events(stored_access_token).await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["access_token_event"], "reused");
+        assert_eq!(events[0]["merchant_id"], "merchant_1");
+        assert_eq!(events[0]["connector"], "airwallex");
+        assert!(events[0]
+            .values()
+            .all(|value| !value.contains("secret_token_1")));
+    }
+
+    #[actix_rt::test]
+    async fn test_expired_access_token_is_logged() {
+        let events = get_logged_lifecycle_events(get_access_token(3600, Some(1_000))).await;
+
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| event["access_token_event"].as_str())
+                .collect::<Vec<_>>(),
+            vec!["expired"]
+        );
+    }
+}
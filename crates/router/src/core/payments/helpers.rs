@@ -2041,6 +2041,20 @@ impl MerchantConnectorAccountType {
         }
     }
 
+    pub fn get_test_mode(&self) -> Option<bool> {
+        match self {
+            Self::DbVal(val) => val.test_mode,
+            Self::CacheVal(_) => None,
+        }
+    }
+
+    pub fn get_mca_id(&self) -> Option<String> {
+        match self {
+            Self::DbVal(val) => Some(val.merchant_connector_id.to_owned()),
+            Self::CacheVal(_) => None,
+        }
+    }
+
     pub fn is_disabled(&self) -> bool {
         match self {
             Self::DbVal(ref inner) => inner.disabled.unwrap_or(false),
@@ -2143,6 +2157,9 @@ pub fn router_data_type_conversion<F1, F2, Req1, Req2, Res1, Res2>(
         customer_id: router_data.customer_id,
         connector_customer: router_data.connector_customer,
         preprocessing_id: router_data.preprocessing_id,
+        test_mode: router_data.test_mode,
+        merchant_connector_id: router_data.merchant_connector_id,
+        correlation_metadata: router_data.correlation_metadata,
     }
 }
 
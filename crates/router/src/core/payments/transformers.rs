@@ -1,4 +1,4 @@
-use std::{fmt::Debug, marker::PhantomData};
+use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
 
 use api_models::payments::OrderDetailsWithAmount;
 use common_utils::fp_utils;
@@ -95,6 +95,16 @@ where
     };
 
     let customer_id = customer.to_owned().map(|customer| customer.customer_id);
+    let correlation_metadata = types::CorrelationMetadata {
+        identifiers: customer_id
+            .iter()
+            .map(|customer_id| ("customer_id".to_string(), customer_id.clone()))
+            .collect(),
+        tags: HashMap::from([(
+            "business_country".to_string(),
+            payment_data.payment_intent.business_country.to_string(),
+        )]),
+    };
 
     router_data = types::RouterData {
         flow: PhantomData,
@@ -124,6 +134,9 @@ where
         payment_method_token: payment_data.pm_token,
         connector_customer: payment_data.connector_customer_id,
         preprocessing_id: payment_data.payment_attempt.preprocessing_step_id,
+        test_mode: merchant_connector_account.get_test_mode(),
+        merchant_connector_id: merchant_connector_account.get_mca_id(),
+        correlation_metadata,
     };
 
     Ok(router_data)
@@ -107,6 +107,9 @@ pub async fn construct_refund_router_data<'a, F>(
         payment_method_token: None,
         connector_customer: None,
         preprocessing_id: None,
+        test_mode: merchant_connector_account.get_test_mode(),
+        merchant_connector_id: merchant_connector_account.get_mca_id(),
+        correlation_metadata: types::CorrelationMetadata::default(),
     };
 
     Ok(router_data)
@@ -294,6 +297,9 @@ pub async fn construct_accept_dispute_router_data<'a>(
         connector_customer: None,
         customer_id: None,
         preprocessing_id: None,
+        test_mode: merchant_connector_account.get_test_mode(),
+        merchant_connector_id: merchant_connector_account.get_mca_id(),
+        correlation_metadata: types::CorrelationMetadata::default(),
     };
     Ok(router_data)
 }
@@ -355,6 +361,9 @@ pub async fn construct_submit_evidence_router_data<'a>(
         connector_customer: None,
         customer_id: None,
         preprocessing_id: None,
+        test_mode: merchant_connector_account.get_test_mode(),
+        merchant_connector_id: merchant_connector_account.get_mca_id(),
+        correlation_metadata: types::CorrelationMetadata::default(),
     };
     Ok(router_data)
 }
@@ -417,6 +426,9 @@ pub async fn construct_upload_file_router_data<'a>(
         connector_customer: None,
         customer_id: None,
         preprocessing_id: None,
+        test_mode: merchant_connector_account.get_test_mode(),
+        merchant_connector_id: merchant_connector_account.get_mca_id(),
+        correlation_metadata: types::CorrelationMetadata::default(),
     };
     Ok(router_data)
 }
@@ -481,6 +493,9 @@ pub async fn construct_defend_dispute_router_data<'a>(
         customer_id: None,
         connector_customer: None,
         preprocessing_id: None,
+        test_mode: merchant_connector_account.get_test_mode(),
+        merchant_connector_id: merchant_connector_account.get_mca_id(),
+        correlation_metadata: types::CorrelationMetadata::default(),
     };
     Ok(router_data)
 }
@@ -543,6 +558,9 @@ pub async fn construct_retrieve_file_router_data<'a>(
         reference_id: None,
         payment_method_token: None,
         preprocessing_id: None,
+        test_mode: merchant_connector_account.get_test_mode(),
+        merchant_connector_id: merchant_connector_account.get_mca_id(),
+        correlation_metadata: types::CorrelationMetadata::default(),
     };
     Ok(router_data)
 }
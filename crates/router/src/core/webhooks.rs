@@ -1,3 +1,4 @@
+pub mod ordering;
 pub mod transformers;
 pub mod utils;
 
@@ -693,71 +694,131 @@ pub async fn webhooks_core<W: api::OutgoingWebhookType>(
             .switch()
             .attach_printable("Could not find object reference id in incoming webhook body")?;
 
-        let event_object = connector
-            .get_webhook_resource_object(&request_details)
-            .switch()
-            .attach_printable("Could not find resource object in incoming webhook body")?;
-
-        let webhook_details = api::IncomingWebhookDetails {
-            object_reference_id: object_ref_id,
-            resource_object: Encode::<serde_json::Value>::encode_to_vec(&event_object)
-                .change_context(errors::ApiErrorResponse::InternalServerError)
-                .attach_printable(
-                    "There was an issue when encoding the incoming webhook body to bytes",
-                )?,
-        };
-
-        match flow_type {
-            api::WebhookFlow::Payment => payments_incoming_webhook_flow::<W>(
-                state.clone(),
-                merchant_account,
-                key_store,
-                webhook_details,
-                source_verified,
-            )
-            .await
-            .attach_printable("Incoming webhook flow for payments failed")?,
-
-            api::WebhookFlow::Refund => refunds_incoming_webhook_flow::<W>(
-                state.clone(),
-                merchant_account,
-                key_store,
-                webhook_details,
+        let delivery_details = ordering::WebhookDeliveryDetails::new(
+            connector
+                .get_webhook_delivery_id(&request_details)
+                .switch()
+                .attach_printable("Could not find delivery id in incoming webhook body")?,
+            connector
+                .get_webhook_event_timestamp(&request_details)
+                .switch()
+                .attach_printable("Could not find event timestamp in incoming webhook body")?,
+            &object_ref_id,
+        );
+
+        // Only verified webhooks are checked and recorded, so that forged deliveries cannot cause
+        // genuine ones to be skipped
+        if source_verified {
+            let ordering_decision = ordering::check_webhook_ordering(
+                state,
+                &merchant_account.merchant_id,
                 connector_name,
-                source_verified,
-                event_type,
+                &delivery_details,
             )
-            .await
-            .attach_printable("Incoming webhook flow for refunds failed")?,
+            .await;
+            if ordering_decision != ordering::WebhookOrderingDecision::Process {
+                logger::info!(?ordering_decision, "Skipping incoming webhook");
+                return connector
+                    .get_webhook_api_response(&request_details)
+                    .switch()
+                    .attach_printable(
+                        "Could not get incoming webhook api response from connector",
+                    );
+            }
+        }
+        let merchant_id = merchant_account.merchant_id.clone();
+
+        let flow_result: CustomResult<(), errors::ApiErrorResponse> = async {
+            let event_object = connector
+                .get_webhook_resource_object(&request_details)
+                .switch()
+                .attach_printable("Could not find resource object in incoming webhook body")?;
+
+            let webhook_details = api::IncomingWebhookDetails {
+                object_reference_id: object_ref_id,
+                resource_object: Encode::<serde_json::Value>::encode_to_vec(&event_object)
+                    .change_context(errors::ApiErrorResponse::InternalServerError)
+                    .attach_printable(
+                        "There was an issue when encoding the incoming webhook body to bytes",
+                    )?,
+            };
 
-            api::WebhookFlow::Dispute => disputes_incoming_webhook_flow::<W>(
-                state.clone(),
-                merchant_account,
-                webhook_details,
-                source_verified,
-                *connector,
-                &request_details,
-                event_type,
-            )
-            .await
-            .attach_printable("Incoming webhook flow for disputes failed")?,
+            match flow_type {
+                api::WebhookFlow::Payment => payments_incoming_webhook_flow::<W>(
+                    state.clone(),
+                    merchant_account,
+                    key_store,
+                    webhook_details,
+                    source_verified,
+                )
+                .await
+                .attach_printable("Incoming webhook flow for payments failed")?,
 
-            api::WebhookFlow::BankTransfer => bank_transfer_webhook_flow::<W>(
-                state.clone(),
-                merchant_account,
-                key_store,
-                webhook_details,
-                source_verified,
-            )
-            .await
-            .attach_printable("Incoming bank-transfer webhook flow failed")?,
+                api::WebhookFlow::Refund => refunds_incoming_webhook_flow::<W>(
+                    state.clone(),
+                    merchant_account,
+                    key_store,
+                    webhook_details,
+                    connector_name,
+                    source_verified,
+                    event_type,
+                )
+                .await
+                .attach_printable("Incoming webhook flow for refunds failed")?,
+
+                api::WebhookFlow::Dispute => disputes_incoming_webhook_flow::<W>(
+                    state.clone(),
+                    merchant_account,
+                    webhook_details,
+                    source_verified,
+                    *connector,
+                    &request_details,
+                    event_type,
+                )
+                .await
+                .attach_printable("Incoming webhook flow for disputes failed")?,
+
+                api::WebhookFlow::BankTransfer => bank_transfer_webhook_flow::<W>(
+                    state.clone(),
+                    merchant_account,
+                    key_store,
+                    webhook_details,
+                    source_verified,
+                )
+                .await
+                .attach_printable("Incoming bank-transfer webhook flow failed")?,
 
-            api::WebhookFlow::ReturnResponse => {}
+                api::WebhookFlow::ReturnResponse => {}
 
-            _ => Err(errors::ApiErrorResponse::InternalServerError)
-                .into_report()
-                .attach_printable("Unsupported Flow Type received in incoming webhooks")?,
+                _ => Err(errors::ApiErrorResponse::InternalServerError)
+                    .into_report()
+                    .attach_printable("Unsupported Flow Type received in incoming webhooks")?,
+            }
+
+            Ok(())
+        }
+        .await;
+
+        if source_verified {
+            if flow_result.is_ok() {
+                ordering::record_processed_webhook(
+                    state,
+                    &merchant_id,
+                    connector_name,
+                    &delivery_details,
+                )
+                .await;
+            } else {
+                ordering::release_webhook_delivery(
+                    state,
+                    &merchant_id,
+                    connector_name,
+                    &delivery_details,
+                )
+                .await;
+            }
         }
+        flow_result?;
     }
 
     let response = connector
@@ -0,0 +1,297 @@
+use api_models::{payments::PaymentIdType, webhooks as webhook_models};
+use redis_interface::SetnxReply;
+use router_env::{instrument, tracing};
+
+use crate::{
+    configs::settings::WebhookOrderingPolicy,
+    core::metrics,
+    logger,
+    routes::{metrics::request::add_attributes, AppState},
+    services::RedisConnInterface,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookOrderingDecision {
+    Process,
+    /// The delivery was already processed
+    Duplicate,
+    /// A newer event of the same object was already processed
+    OutOfOrder,
+}
+
+/// Details of an incoming webhook used to detect duplicate and out of order deliveries
+pub struct WebhookDeliveryDetails {
+    pub delivery_id: Option<String>,
+    /// Time at which the event occurred at the connector, as a unix timestamp
+    pub event_timestamp: Option<i64>,
+    pub object_reference: String,
+}
+
+impl WebhookDeliveryDetails {
+    pub fn new(
+        delivery_id: Option<String>,
+        event_timestamp: Option<i64>,
+        object_reference_id: &webhook_models::ObjectReferenceId,
+    ) -> Self {
+        Self {
+            delivery_id,
+            event_timestamp,
+            object_reference: get_object_reference_key(object_reference_id),
+        }
+    }
+}
+
+fn get_object_reference_key(object_reference_id: &webhook_models::ObjectReferenceId) -> String {
+    match object_reference_id {
+        webhook_models::ObjectReferenceId::PaymentId(payment_id) => match payment_id {
+            PaymentIdType::PaymentIntentId(id) => format!("payment_intent_{id}"),
+            PaymentIdType::ConnectorTransactionId(id) => format!("connector_transaction_{id}"),
+            PaymentIdType::PaymentAttemptId(id) => format!("payment_attempt_{id}"),
+            PaymentIdType::PreprocessingId(id) => format!("preprocessing_{id}"),
+        },
+        webhook_models::ObjectReferenceId::RefundId(refund_id) => match refund_id {
+            webhook_models::RefundIdType::RefundId(id) => format!("refund_{id}"),
+            webhook_models::RefundIdType::ConnectorRefundId(id) => format!("connector_refund_{id}"),
+        },
+    }
+}
+
+fn get_delivery_key(merchant_id: &str, connector: &str, delivery_id: &str) -> String {
+    format!("whdelivery_{merchant_id}_{connector}_{delivery_id}")
+}
+
+fn get_event_timestamp_key(merchant_id: &str, connector: &str, object_reference: &str) -> String {
+    format!("whevent_ts_{merchant_id}_{connector}_{object_reference}")
+}
+
+/// Store the event timestamp unless a newer one was already stored, so that concurrent deliveries
+/// of the same object cannot move the last processed event timestamp backwards
+const SET_EVENT_TIMESTAMP_IF_NEWER_SCRIPT: &str = r#"
+local current = redis.call("GET", KEYS[1])
+if current == false or tonumber(current) < tonumber(ARGV[1]) then
+    redis.call("SET", KEYS[1], ARGV[1], "EX", ARGV[2])
+    return 1
+end
+return 0
+"#;
+
+fn decide(
+    policy: WebhookOrderingPolicy,
+    is_already_delivered: bool,
+    last_processed_timestamp: Option<i64>,
+    event_timestamp: Option<i64>,
+) -> WebhookOrderingDecision {
+    if policy.deduplicate && is_already_delivered {
+        return WebhookOrderingDecision::Duplicate;
+    }
+
+    match (last_processed_timestamp, event_timestamp) {
+        (Some(last_processed_timestamp), Some(event_timestamp))
+            if policy.reject_out_of_order && event_timestamp < last_processed_timestamp =>
+        {
+            WebhookOrderingDecision::OutOfOrder
+        }
+        _ => WebhookOrderingDecision::Process,
+    }
+}
+
+/// Check an incoming webhook against the ordering policy of the connector. The delivery id is
+/// claimed atomically, so that concurrent deliveries of the same id are processed only once, the
+/// claim has to be released with `release_webhook_delivery` if processing the webhook fails.
+/// Failures to reach Redis are only logged, so that webhooks are never lost because of them.
+#[instrument(skip_all)]
+pub async fn check_webhook_ordering(
+    state: &AppState,
+    merchant_id: &str,
+    connector: &str,
+    delivery_details: &WebhookDeliveryDetails,
+) -> WebhookOrderingDecision {
+    let policy = match state.conf.webhook_ordering.connectors.get(connector) {
+        Some(policy) => *policy,
+        None => return WebhookOrderingDecision::Process,
+    };
+    let redis_conn = state.store.get_redis_conn();
+
+    let is_already_delivered = match &delivery_details.delivery_id {
+        Some(delivery_id) if policy.deduplicate => redis_conn
+            .set_key_if_not_exists_with_expiry(
+                &get_delivery_key(merchant_id, connector, delivery_id),
+                "processed",
+                state.conf.webhook_ordering.retention_secs,
+            )
+            .await
+            .map_err(|error| logger::error!(webhook_delivery_claim_error=?error))
+            .map(|reply| reply == SetnxReply::KeyNotSet)
+            .unwrap_or(false),
+        _ => false,
+    };
+
+    let last_processed_timestamp = if policy.reject_out_of_order {
+        redis_conn
+            .get_key::<Option<i64>>(&get_event_timestamp_key(
+                merchant_id,
+                connector,
+                &delivery_details.object_reference,
+            ))
+            .await
+            .map_err(|error| logger::error!(webhook_event_timestamp_lookup_error=?error))
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+
+    let decision = decide(
+        policy,
+        is_already_delivered,
+        last_processed_timestamp,
+        delivery_details.event_timestamp,
+    );
+    if decision != WebhookOrderingDecision::Process {
+        metrics::INCOMING_WEBHOOK_SKIPPED_METRIC.add(
+            &metrics::CONTEXT,
+            1,
+            &[
+                add_attributes("connector", connector.to_string()),
+                add_attributes("reason", format!("{decision:?}")),
+            ],
+        );
+    }
+    decision
+}
+
+/// Remember the event timestamp of a processed webhook, so that older events of the same object
+/// can be discarded
+#[instrument(skip_all)]
+pub async fn record_processed_webhook(
+    state: &AppState,
+    merchant_id: &str,
+    connector: &str,
+    delivery_details: &WebhookDeliveryDetails,
+) {
+    let policy = match state.conf.webhook_ordering.connectors.get(connector) {
+        Some(policy) => *policy,
+        None => return,
+    };
+
+    if let Some(event_timestamp) = delivery_details
+        .event_timestamp
+        .filter(|_| policy.reject_out_of_order)
+    {
+        let result: Result<i64, _> = state
+            .store
+            .get_redis_conn()
+            .evaluate_redis_script(
+                SET_EVENT_TIMESTAMP_IF_NEWER_SCRIPT,
+                vec![get_event_timestamp_key(
+                    merchant_id,
+                    connector,
+                    &delivery_details.object_reference,
+                )],
+                vec![
+                    event_timestamp.to_string(),
+                    state.conf.webhook_ordering.retention_secs.to_string(),
+                ],
+            )
+            .await;
+        if let Err(error) = result {
+            logger::error!(webhook_event_timestamp_store_error=?error);
+        }
+    }
+}
+
+/// Release the delivery id claimed by `check_webhook_ordering` after processing the webhook
+/// failed, so that the connector can deliver it again
+#[instrument(skip_all)]
+pub async fn release_webhook_delivery(
+    state: &AppState,
+    merchant_id: &str,
+    connector: &str,
+    delivery_details: &WebhookDeliveryDetails,
+) {
+    let policy = match state.conf.webhook_ordering.connectors.get(connector) {
+        Some(policy) => *policy,
+        None => return,
+    };
+
+    if let Some(delivery_id) = delivery_details
+        .delivery_id
+        .as_ref()
+        .filter(|_| policy.deduplicate)
+    {
+        if let Err(error) = state
+            .store
+            .get_redis_conn()
+            .delete_key(&get_delivery_key(merchant_id, connector, delivery_id))
+            .await
+        {
+            logger::error!(webhook_delivery_release_error=?error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: WebhookOrderingPolicy = WebhookOrderingPolicy {
+        deduplicate: true,
+        reject_out_of_order: true,
+    };
+
+    #[test]
+    fn test_duplicate_delivery_is_skipped() {
+        assert_eq!(
+            decide(POLICY, true, Some(100), Some(200)),
+            WebhookOrderingDecision::Duplicate
+        );
+        assert_eq!(
+            decide(
+                WebhookOrderingPolicy {
+                    deduplicate: false,
+                    ..POLICY
+                },
+                true,
+                Some(100),
+                Some(200)
+            ),
+            WebhookOrderingDecision::Process
+        );
+    }
+
+    #[test]
+    fn test_older_event_is_skipped() {
+        assert_eq!(
+            decide(POLICY, false, Some(200), Some(100)),
+            WebhookOrderingDecision::OutOfOrder
+        );
+        assert_eq!(
+            decide(
+                WebhookOrderingPolicy {
+                    reject_out_of_order: false,
+                    ..POLICY
+                },
+                false,
+                Some(200),
+                Some(100)
+            ),
+            WebhookOrderingDecision::Process
+        );
+    }
+
+    #[test]
+    fn test_newer_event_is_processed() {
+        assert_eq!(
+            decide(POLICY, false, Some(100), Some(200)),
+            WebhookOrderingDecision::Process
+        );
+        assert_eq!(
+            decide(POLICY, false, None, Some(100)),
+            WebhookOrderingDecision::Process
+        );
+        assert_eq!(
+            decide(POLICY, false, Some(100), None),
+            WebhookOrderingDecision::Process
+        );
+    }
+}
@@ -28,7 +28,7 @@ use futures::lock::Mutex;
 
 use crate::{
     services::{self, Store},
-    types::storage,
+    types::{self, storage},
 };
 
 #[derive(PartialEq, Eq)]
@@ -115,6 +115,11 @@ pub struct MockDb {
     disputes: Arc<Mutex<Vec<storage::Dispute>>>,
     lockers: Arc<Mutex<Vec<storage::LockerMockUp>>>,
     mandates: Arc<Mutex<Vec<storage::Mandate>>>,
+    /// Access tokens, keyed by merchant id and connector name
+    access_tokens: Arc<Mutex<std::collections::HashMap<String, types::AccessToken>>>,
+    /// Expiry time of the invalid credentials markers, keyed by merchant id and connector name
+    invalid_credentials_markers: Arc<Mutex<std::collections::HashMap<String, i64>>>,
+    access_token_locks: Arc<Mutex<std::collections::HashMap<String, String>>>,
 }
 
 impl MockDb {
@@ -138,6 +143,9 @@ impl MockDb {
             disputes: Default::default(),
             lockers: Default::default(),
             mandates: Default::default(),
+            access_tokens: Default::default(),
+            invalid_credentials_markers: Default::default(),
+            access_token_locks: Default::default(),
         }
     }
 }
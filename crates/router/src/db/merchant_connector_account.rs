@@ -1,5 +1,6 @@
 use common_utils::ext_traits::{AsyncExt, ByteSliceExt, Encode};
 use error_stack::{IntoReport, ResultExt};
+use redis_interface::SetnxReply;
 
 #[cfg(feature = "accounts_cache")]
 use super::cache;
@@ -32,19 +33,102 @@ pub trait ConnectorAccessToken {
         connector_name: &str,
         access_token: types::AccessToken,
     ) -> CustomResult<(), errors::StorageError>;
+
+    /// Store the access token, unless the stored access token expires later because a concurrent
+    /// refresh stored a newer access token first. Returns the access token kept in the store.
+    async fn compare_and_set_access_token(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+        access_token: types::AccessToken,
+    ) -> CustomResult<types::AccessToken, errors::StorageError>;
+
+    async fn delete_access_token(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+    ) -> CustomResult<(), errors::StorageError>;
+
+    /// Remember for `ttl` seconds that the connector rejected the credentials of the
+    /// merchant connector account
+    async fn set_invalid_credentials_marker(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+        ttl: i64,
+    ) -> CustomResult<(), errors::StorageError>;
+
+    async fn is_invalid_credentials_marker_set(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+    ) -> CustomResult<bool, errors::StorageError>;
+
+    async fn delete_invalid_credentials_marker(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+    ) -> CustomResult<(), errors::StorageError>;
+
+    /// Acquire the lock for `ttl` seconds, holding it with `lock_value` which has to be unique to
+    /// the request. Returns true if the lock was acquired, false if it is already held by another
+    /// request.
+    async fn acquire_access_token_lock(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+        lock_value: &str,
+        ttl: i64,
+    ) -> CustomResult<bool, errors::StorageError>;
+
+    /// Release the lock, only if it is still held with `lock_value`. The lock may have expired and
+    /// been acquired by another request in the meantime.
+    async fn release_access_token_lock(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+        lock_value: &str,
+    ) -> CustomResult<(), errors::StorageError>;
 }
 
+/// Delete the lock only if it is still held with the given value
+const RELEASE_LOCK_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+else
+    return 0
+end
+"#;
+
+/// Store the access token unless the stored access token expires later. The access token with an
+/// unknown creation time is always replaced, as in [`types::AccessToken::should_replace`].
+/// Arguments are the serialized access token, its expiry as a unix timestamp (empty if unknown)
+/// and its lifetime in seconds. Returns the access token kept in the store.
+const COMPARE_AND_SET_ACCESS_TOKEN_SCRIPT: &str = r#"
+local existing = redis.call("GET", KEYS[1])
+if existing and ARGV[2] ~= "" then
+    local existing_access_token = cjson.decode(existing)
+    local created_at = existing_access_token["access_token_created_at"]
+    if type(created_at) == "number"
+        and created_at + existing_access_token["expires"] > tonumber(ARGV[2]) then
+        return existing
+    end
+end
+redis.call("SET", KEYS[1], ARGV[1], "EX", ARGV[3])
+return ARGV[1]
+"#;
+
 #[async_trait::async_trait]
 impl ConnectorAccessToken for Store {
     async fn get_access_token(
         &self,
         merchant_id: &str,
-        connector_name: &str,
+        merchant_connector_id: &str,
     ) -> CustomResult<Option<types::AccessToken>, errors::StorageError> {
         //TODO: Handle race condition
         // This function should acquire a global lock on some resource, if access token is already
         // being refreshed by other request then wait till it finishes and use the same access token
-        let key = format!("access_token_{merchant_id}_{connector_name}");
+        let key = format!("access_token_{merchant_id}_{merchant_connector_id}");
         let maybe_token = self
             .redis_conn()
             .map_err(Into::<errors::StorageError>::into)?
@@ -82,24 +166,268 @@ impl ConnectorAccessToken for Store {
             })
             .into_report()
     }
+
+    async fn compare_and_set_access_token(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+        access_token: types::AccessToken,
+    ) -> CustomResult<types::AccessToken, errors::StorageError> {
+        let key = format!("access_token_{merchant_id}_{connector_name}");
+        let serialized_access_token =
+            Encode::<types::AccessToken>::encode_to_string_of_json(&access_token)
+                .change_context(errors::StorageError::SerializationFailed)?;
+        let expires_at = access_token
+            .expires_at()
+            .map(|expires_at| expires_at.to_string())
+            .unwrap_or_default();
+        let stored_access_token: String = self
+            .redis_conn()
+            .map_err(Into::<errors::StorageError>::into)?
+            .evaluate_redis_script(
+                COMPARE_AND_SET_ACCESS_TOKEN_SCRIPT,
+                vec![key],
+                vec![
+                    serialized_access_token,
+                    expires_at,
+                    access_token.expires.to_string(),
+                ],
+            )
+            .await
+            .change_context(errors::StorageError::KVError)
+            .attach_printable("DB error when storing the access token")?;
+
+        stored_access_token
+            .as_bytes()
+            .parse_struct("AccessToken")
+            .change_context(errors::StorageError::DeserializationFailed)
+    }
+
+    async fn delete_access_token(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+    ) -> CustomResult<(), errors::StorageError> {
+        let key = format!("access_token_{merchant_id}_{connector_name}");
+        self.redis_conn()
+            .map_err(Into::<errors::StorageError>::into)?
+            .delete_key(&key)
+            .await
+            .change_context(errors::StorageError::KVError)
+            .attach_printable("DB error when deleting the access token")?;
+        Ok(())
+    }
+
+    async fn set_invalid_credentials_marker(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+        ttl: i64,
+    ) -> CustomResult<(), errors::StorageError> {
+        let key = format!("invalid_credentials_{merchant_id}_{merchant_connector_id}");
+        self.redis_conn()
+            .map_err(Into::<errors::StorageError>::into)?
+            .set_key_with_expiry(&key, "INVALID", ttl)
+            .await
+            .change_context(errors::StorageError::KVError)
+            .attach_printable("DB error when setting the invalid credentials marker")
+    }
+
+    async fn is_invalid_credentials_marker_set(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+    ) -> CustomResult<bool, errors::StorageError> {
+        let key = format!("invalid_credentials_{merchant_id}_{merchant_connector_id}");
+        let marker = self
+            .redis_conn()
+            .map_err(Into::<errors::StorageError>::into)?
+            .get_key::<Option<String>>(&key)
+            .await
+            .change_context(errors::StorageError::KVError)
+            .attach_printable("DB error when getting the invalid credentials marker")?;
+        Ok(marker.is_some())
+    }
+
+    async fn delete_invalid_credentials_marker(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+    ) -> CustomResult<(), errors::StorageError> {
+        let key = format!("invalid_credentials_{merchant_id}_{merchant_connector_id}");
+        self.redis_conn()
+            .map_err(Into::<errors::StorageError>::into)?
+            .delete_key(&key)
+            .await
+            .change_context(errors::StorageError::KVError)
+            .attach_printable("DB error when deleting the invalid credentials marker")?;
+        Ok(())
+    }
+
+    async fn acquire_access_token_lock(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+        lock_value: &str,
+        ttl: i64,
+    ) -> CustomResult<bool, errors::StorageError> {
+        let key = format!("access_token_lock_{merchant_id}_{connector_name}");
+        let is_lock_acquired = self
+            .redis_conn()
+            .map_err(Into::<errors::StorageError>::into)?
+            .set_key_if_not_exists_with_expiry(&key, lock_value, ttl)
+            .await
+            .change_context(errors::StorageError::KVError)
+            .attach_printable("DB error when acquiring the access token lock")?;
+
+        Ok(matches!(is_lock_acquired, SetnxReply::KeySet))
+    }
+
+    async fn release_access_token_lock(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+        lock_value: &str,
+    ) -> CustomResult<(), errors::StorageError> {
+        let key = format!("access_token_lock_{merchant_id}_{connector_name}");
+        let _: i64 = self
+            .redis_conn()
+            .map_err(Into::<errors::StorageError>::into)?
+            .evaluate_redis_script(RELEASE_LOCK_SCRIPT, vec![key], vec![lock_value.to_string()])
+            .await
+            .change_context(errors::StorageError::KVError)
+            .attach_printable("DB error when releasing the access token lock")?;
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
 impl ConnectorAccessToken for MockDb {
     async fn get_access_token(
         &self,
-        _merchant_id: &str,
-        _connector_name: &str,
+        merchant_id: &str,
+        connector_name: &str,
     ) -> CustomResult<Option<types::AccessToken>, errors::StorageError> {
-        Ok(None)
+        Ok(self
+            .access_tokens
+            .lock()
+            .await
+            .get(&format!("{merchant_id}_{connector_name}"))
+            .cloned())
     }
 
     async fn set_access_token(
         &self,
-        _merchant_id: &str,
-        _connector_name: &str,
-        _access_token: types::AccessToken,
+        merchant_id: &str,
+        connector_name: &str,
+        access_token: types::AccessToken,
+    ) -> CustomResult<(), errors::StorageError> {
+        self.access_tokens
+            .lock()
+            .await
+            .insert(format!("{merchant_id}_{connector_name}"), access_token);
+        Ok(())
+    }
+
+    async fn compare_and_set_access_token(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+        access_token: types::AccessToken,
+    ) -> CustomResult<types::AccessToken, errors::StorageError> {
+        let mut access_tokens = self.access_tokens.lock().await;
+        let key = format!("{merchant_id}_{connector_name}");
+        match access_tokens.get(&key) {
+            Some(existing_access_token) if !access_token.should_replace(existing_access_token) => {
+                Ok(existing_access_token.clone())
+            }
+            _ => {
+                access_tokens.insert(key, access_token.clone());
+                Ok(access_token)
+            }
+        }
+    }
+
+    async fn delete_access_token(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+    ) -> CustomResult<(), errors::StorageError> {
+        self.access_tokens
+            .lock()
+            .await
+            .remove(&format!("{merchant_id}_{connector_name}"));
+        Ok(())
+    }
+
+    async fn set_invalid_credentials_marker(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+        ttl: i64,
     ) -> CustomResult<(), errors::StorageError> {
+        let expires_at = common_utils::date_time::now_unix_timestamp().saturating_add(ttl);
+        self.invalid_credentials_markers
+            .lock()
+            .await
+            .insert(format!("{merchant_id}_{merchant_connector_id}"), expires_at);
+        Ok(())
+    }
+
+    async fn is_invalid_credentials_marker_set(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+    ) -> CustomResult<bool, errors::StorageError> {
+        let current_time = common_utils::date_time::now_unix_timestamp();
+        Ok(self
+            .invalid_credentials_markers
+            .lock()
+            .await
+            .get(&format!("{merchant_id}_{merchant_connector_id}"))
+            .map(|expires_at| *expires_at > current_time)
+            .unwrap_or(false))
+    }
+
+    async fn delete_invalid_credentials_marker(
+        &self,
+        merchant_id: &str,
+        merchant_connector_id: &str,
+    ) -> CustomResult<(), errors::StorageError> {
+        self.invalid_credentials_markers
+            .lock()
+            .await
+            .remove(&format!("{merchant_id}_{merchant_connector_id}"));
+        Ok(())
+    }
+
+    async fn acquire_access_token_lock(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+        lock_value: &str,
+        _ttl: i64,
+    ) -> CustomResult<bool, errors::StorageError> {
+        let mut access_token_locks = self.access_token_locks.lock().await;
+        let key = format!("{merchant_id}_{connector_name}");
+        if access_token_locks.contains_key(&key) {
+            return Ok(false);
+        }
+        access_token_locks.insert(key, lock_value.to_string());
+        Ok(true)
+    }
+
+    async fn release_access_token_lock(
+        &self,
+        merchant_id: &str,
+        connector_name: &str,
+        lock_value: &str,
+    ) -> CustomResult<(), errors::StorageError> {
+        let mut access_token_locks = self.access_token_locks.lock().await;
+        let key = format!("{merchant_id}_{connector_name}");
+        if access_token_locks.get(&key).map(String::as_str) == Some(lock_value) {
+            access_token_locks.remove(&key);
+        }
         Ok(())
     }
 }
@@ -507,3 +835,158 @@ impl MerchantConnectorAccountInterface for MockDb {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use masking::{PeekInterface, Secret};
+
+    use crate::{
+        db::{merchant_connector_account::ConnectorAccessToken, MockDb},
+        types,
+    };
+
+    fn get_access_token(token: &str, created_at: i64) -> types::AccessToken {
+        types::AccessToken {
+            token: Secret::new(token.to_string()),
+            expires: 3600,
+            access_token_created_at: Some(created_at),
+        }
+    }
+
+    #[allow(clippy::unwrap_used)]
+    #[tokio::test]
+    async fn test_mockdb_concurrent_access_token_refreshes_keep_newer_token() {
+        let mockdb = MockDb::new(&Default::default()).await;
+        let older_access_token = get_access_token("older", 1000);
+        let newer_access_token = get_access_token("newer", 2000);
+
+        // The slower refresh stores the older access token after the newer one was stored
+        let (newer_result, older_result) = tokio::join!(
+            mockdb.compare_and_set_access_token(
+                "merchant_1",
+                "airwallex",
+                newer_access_token.clone()
+            ),
+            mockdb.compare_and_set_access_token(
+                "merchant_1",
+                "airwallex",
+                older_access_token.clone()
+            ),
+        );
+
+        assert_eq!(newer_result.unwrap().token.peek(), "newer");
+        assert_eq!(older_result.unwrap().token.peek(), "newer");
+        assert_eq!(
+            mockdb
+                .get_access_token("merchant_1", "airwallex")
+                .await
+                .unwrap()
+                .unwrap()
+                .token
+                .peek(),
+            "newer"
+        );
+    }
+
+    #[allow(clippy::unwrap_used)]
+    #[tokio::test]
+    async fn test_mockdb_access_token_replaces_expired_token() {
+        let mockdb = MockDb::new(&Default::default()).await;
+
+        mockdb
+            .set_access_token("merchant_1", "airwallex", get_access_token("expired", 1000))
+            .await
+            .unwrap();
+        let stored_access_token = mockdb
+            .compare_and_set_access_token(
+                "merchant_1",
+                "airwallex",
+                get_access_token("refreshed", 5000),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(stored_access_token.token.peek(), "refreshed");
+    }
+
+    #[allow(clippy::unwrap_used)]
+    #[tokio::test]
+    async fn test_mockdb_access_token_lock_is_released_only_by_its_holder() {
+        let mockdb = MockDb::new(&Default::default()).await;
+
+        assert!(mockdb
+            .acquire_access_token_lock("merchant_1", "airwallex", "request_1", 10)
+            .await
+            .unwrap());
+        assert!(!mockdb
+            .acquire_access_token_lock("merchant_1", "airwallex", "request_2", 10)
+            .await
+            .unwrap());
+
+        mockdb
+            .release_access_token_lock("merchant_1", "airwallex", "request_2")
+            .await
+            .unwrap();
+        assert!(!mockdb
+            .acquire_access_token_lock("merchant_1", "airwallex", "request_2", 10)
+            .await
+            .unwrap());
+
+        mockdb
+            .release_access_token_lock("merchant_1", "airwallex", "request_1")
+            .await
+            .unwrap();
+        assert!(mockdb
+            .acquire_access_token_lock("merchant_1", "airwallex", "request_2", 10)
+            .await
+            .unwrap());
+    }
+
+    #[allow(clippy::unwrap_used)]
+    #[tokio::test]
+    async fn test_mockdb_invalid_credentials_marker() {
+        let mockdb = MockDb::new(&Default::default()).await;
+
+        assert!(!mockdb
+            .is_invalid_credentials_marker_set("merchant_1", "mca_1")
+            .await
+            .unwrap());
+
+        mockdb
+            .set_invalid_credentials_marker("merchant_1", "mca_1", 60)
+            .await
+            .unwrap();
+        assert!(mockdb
+            .is_invalid_credentials_marker_set("merchant_1", "mca_1")
+            .await
+            .unwrap());
+        assert!(!mockdb
+            .is_invalid_credentials_marker_set("merchant_1", "mca_2")
+            .await
+            .unwrap());
+
+        mockdb
+            .delete_invalid_credentials_marker("merchant_1", "mca_1")
+            .await
+            .unwrap();
+        assert!(!mockdb
+            .is_invalid_credentials_marker_set("merchant_1", "mca_1")
+            .await
+            .unwrap());
+    }
+
+    #[allow(clippy::unwrap_used)]
+    #[tokio::test]
+    async fn test_mockdb_invalid_credentials_marker_expires() {
+        let mockdb = MockDb::new(&Default::default()).await;
+
+        mockdb
+            .set_invalid_credentials_marker("merchant_1", "mca_1", 0)
+            .await
+            .unwrap();
+        assert!(!mockdb
+            .is_invalid_credentials_marker_set("merchant_1", "mca_1")
+            .await
+            .unwrap());
+    }
+}
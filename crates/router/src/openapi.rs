@@ -73,6 +73,7 @@ Never share your secret api keys. Keep them guarded and secure.
         // crate::routes::admin::payment_connector_create,
         // crate::routes::admin::payment_connector_retrieve,
         // crate::routes::admin::payment_connector_list,
+        // crate::routes::admin::payment_connectors_health_check,
         // crate::routes::admin::payment_connector_update,
         // crate::routes::admin::payment_connector_delete,
         crate::routes::mandates::get_mandate,
@@ -116,6 +117,8 @@ Never share your secret api keys. Keep them guarded and secure.
         crate::types::api::admin::MerchantAccountDeleteResponse,
         crate::types::api::admin::MerchantConnectorDeleteResponse,
         crate::types::api::admin::MerchantConnectorResponse,
+        crate::types::api::admin::MerchantConnectorHealthCheckResponse,
+        crate::types::api::admin::ConnectorHealthStatus,
         crate::types::api::customers::CustomerRequest,
         crate::types::api::customers::CustomerDeleteResponse,
         crate::types::api::payment_methods::PaymentMethodCreate,
@@ -180,7 +180,9 @@ pub async fn payment_connector_create(
         state.get_ref(),
         &req,
         json_payload.into_inner(),
-        |state, _, req| create_payment_connector(&*state.store, req, &merchant_id),
+        |state, _, req| {
+            create_payment_connector(&*state.store, &state.conf.connectors, req, &merchant_id)
+        },
         &auth::AdminApiAuth,
     )
     .await
@@ -270,6 +272,44 @@ pub async fn payment_connector_list(
     .await
 }
 
+/// Merchant Connector - Health Check
+///
+/// Verify the credentials of the Merchant Connectors of the merchant without running a payment
+#[utoipa::path(
+    get,
+    path = "/accounts/{account_id}/connectors/health",
+    params(
+        ("account_id" = String, Path, description = "The unique identifier for the merchant account"),
+    ),
+    responses(
+        (status = 200, description = "Merchant Connectors checked successfully", body = Vec<MerchantConnectorHealthCheckResponse>),
+        (status = 404, description = "Merchant Account does not exist in records"),
+        (status = 401, description = "Unauthorized request")
+    ),
+    tag = "Merchant Connector Account",
+    operation_id = "Check health of all Merchant Connectors",
+    security(("admin_api_key" = []))
+)]
+#[instrument(skip_all, fields(flow = ?Flow::MerchantConnectorsHealthCheck))]
+pub async fn payment_connectors_health_check(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let flow = Flow::MerchantConnectorsHealthCheck;
+    let merchant_id = path.into_inner();
+
+    api::server_wrap(
+        flow,
+        state.get_ref(),
+        &req,
+        merchant_id,
+        |state, _, merchant_id| check_payment_connectors_health(state, merchant_id),
+        &auth::AdminApiAuth,
+    )
+    .await
+}
+
 /// Merchant Connector - Update
 ///
 /// To update an existing Merchant Connector. Helpful in enabling / disabling different payment methods and other settings for the connector etc.
@@ -306,7 +346,13 @@ pub async fn payment_connector_update(
         &req,
         json_payload.into_inner(),
         |state, _, req| {
-            update_payment_connector(&*state.store, &merchant_id, &merchant_connector_id, req)
+            update_payment_connector(
+                &*state.store,
+                &state.conf.connectors,
+                &merchant_id,
+                &merchant_connector_id,
+                req,
+            )
         },
         &auth::AdminApiAuth,
     )
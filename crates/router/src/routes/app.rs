@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use actix_web::{web, Scope};
 #[cfg(feature = "email")]
 use external_services::email::{AwsSes, EmailClient};
@@ -19,6 +21,7 @@ use crate::{
     db::{MockDb, StorageImpl, StorageInterface},
     routes::cards_info::card_iin_info,
     services::Store,
+    utils::clock::{Clock, SystemClock},
 };
 
 #[derive(Clone)]
@@ -26,6 +29,7 @@ pub struct AppState {
     pub flow_name: String,
     pub store: Box<dyn StorageInterface>,
     pub conf: settings::Settings,
+    pub clock: Arc<dyn Clock>,
     #[cfg(feature = "email")]
     pub email_client: Box<dyn EmailClient>,
     #[cfg(feature = "kms")]
@@ -88,6 +92,7 @@ impl AppState {
             flow_name: String::from("default"),
             store,
             conf,
+            clock: Arc::new(SystemClock),
             #[cfg(feature = "email")]
             email_client,
             #[cfg(feature = "kms")]
@@ -347,6 +352,10 @@ impl MerchantConnectorAccount {
                         .route(web::post().to(payment_connector_create))
                         .route(web::get().to(payment_connector_list)),
                 )
+                .service(
+                    web::resource("/{merchant_id}/connectors/health")
+                        .route(web::get().to(payment_connectors_health_check)),
+                )
                 .service(
                     web::resource("/{merchant_id}/connectors/{merchant_connector_id}")
                         .route(web::get().to(payment_connector_retrieve))
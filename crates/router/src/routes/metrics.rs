@@ -49,10 +49,12 @@ counter_metric!(MCA_CREATE, GLOBAL_METER);
 // Flow Specific Metrics
 
 counter_metric!(ACCESS_TOKEN_CREATION, GLOBAL_METER);
-histogram_metric!(CONNECTOR_REQUEST_TIME, GLOBAL_METER);
+counter_metric!(ACCESS_TOKEN_CACHE_HIT, GLOBAL_METER);
+counter_metric!(ACCESS_TOKEN_CACHE_MISS, GLOBAL_METER);
 counter_metric!(SESSION_TOKEN_CREATED, GLOBAL_METER);
 
 counter_metric!(CONNECTOR_CALL_COUNT, GLOBAL_METER); // Attributes needed
+histogram_metric!(CONNECTOR_REQUEST_DURATION, GLOBAL_METER); // Time taken by the connector to respond
 
 counter_metric!(THREE_DS_PAYMENT_COUNT, GLOBAL_METER);
 counter_metric!(THREE_DS_DOWNGRADE_COUNT, GLOBAL_METER);
@@ -70,6 +72,7 @@ counter_metric!(REDIRECTION_TRIGGERED, GLOBAL_METER);
 // Connector Level Metric
 counter_metric!(REQUEST_BUILD_FAILURE, GLOBAL_METER);
 counter_metric!(UNIMPLEMENTED_FLOW, GLOBAL_METER);
+counter_metric!(CONNECT_RETRY_COUNT, GLOBAL_METER);
 
 // Service Level
 counter_metric!(CARD_LOCKER_FAILURES, GLOBAL_METER);
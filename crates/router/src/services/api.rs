@@ -1,5 +1,7 @@
 mod client;
+pub(crate) mod invalid_credentials;
 pub(crate) mod request;
+mod sampling;
 
 use std::{
     collections::HashMap,
@@ -20,7 +22,7 @@ use serde_json::json;
 use self::request::{ContentType, HeaderExt, RequestBuilderExt};
 pub use self::request::{Method, Request, RequestBuilder};
 use crate::{
-    configs::settings::Connectors,
+    configs::settings::{ConnectorErrorStatus, Connectors},
     consts,
     core::{
         errors::{self, CustomResult},
@@ -189,6 +191,11 @@ pub trait ConnectorIntegration<T, Req, Resp>: ConnectorIntegrationAny<T, Req, Re
 /// Handle the flow by interacting with connector module
 /// `connector_request` is applicable only in case if the `CallConnectorAction` is `Trigger`
 /// In other cases, It will be created if required, even if it is not passed
+/// If the connector rejects the access token with a 401, because it was revoked before its expiry,
+/// the access token is evicted and the request is retried once with a new access token.
+/// Once the connector has rejected the credentials of the merchant connector account, the following
+/// calls with them fail without reaching the connector for a while. Calls carrying an access token
+/// skip that check, the marker is then checked before creating an access token instead.
 #[instrument(skip_all)]
 pub async fn execute_connector_processing_step<
     'b,
@@ -203,6 +210,86 @@ pub async fn execute_connector_processing_step<
     call_connector_action: payments::CallConnectorAction,
     connector_request: Option<Request>,
 ) -> CustomResult<types::RouterData<T, Req, Resp>, errors::ConnectorError>
+where
+    T: Clone + Debug,
+{
+    let is_connector_called = matches!(
+        call_connector_action,
+        payments::CallConnectorAction::Trigger
+    );
+    if is_connector_called
+        && req.access_token.is_none()
+        && invalid_credentials::is_marked(state, req).await
+    {
+        let mut router_data = req.clone();
+        router_data.response = Err(invalid_credentials::get_error_response());
+        return Ok(router_data);
+    }
+
+    let router_data = execute_connector_call(
+        state,
+        connector_integration.clone(),
+        req,
+        call_connector_action,
+        connector_request,
+    )
+    .await?;
+
+    if !is_connector_called {
+        return Ok(router_data);
+    }
+
+    let router_data =
+        if req.access_token.is_some() && is_access_token_rejected(&router_data.response) {
+            match payments::access_token::refresh_rejected_access_token(state, req).await {
+                Some(access_token) => {
+                    logger::warn!(
+                        connector = %req.connector,
+                        "Retrying connector request with a new access token"
+                    );
+                    let mut retry_req = req.clone();
+                    retry_req.access_token = Some(access_token);
+                    execute_connector_call(
+                        state,
+                        connector_integration,
+                        &retry_req,
+                        payments::CallConnectorAction::Trigger,
+                        None,
+                    )
+                    .await?
+                }
+                None => return Ok(router_data),
+            }
+        } else {
+            router_data
+        };
+
+    if let Err(error_response) = &router_data.response {
+        invalid_credentials::mark_if_rejected(state, req, error_response).await;
+    }
+    Ok(router_data)
+}
+
+fn is_access_token_rejected<Resp>(response: &Result<Resp, ErrorResponse>) -> bool {
+    matches!(response, Err(error_response) if error_response.status_code == 401)
+}
+
+/// Same as `execute_connector_processing_step`, without the retry on an access token rejection.
+/// Used for the access token flow itself.
+#[instrument(skip_all)]
+pub async fn execute_connector_call<
+    'b,
+    'a,
+    T: 'static,
+    Req: Debug + Clone + 'static,
+    Resp: Debug + Clone + 'static,
+>(
+    state: &'b AppState,
+    connector_integration: BoxedConnectorIntegration<'a, T, Req, Resp>,
+    req: &'b types::RouterData<T, Req, Resp>,
+    call_connector_action: payments::CallConnectorAction,
+    connector_request: Option<Request>,
+) -> CustomResult<types::RouterData<T, Req, Resp>, errors::ConnectorError>
 where
     T: Clone + Debug,
     // BoxedConnectorIntegration<T, Req, Resp>: 'b,
@@ -241,21 +328,18 @@ where
             Ok(router_data)
         }
         payments::CallConnectorAction::Trigger => {
-            metrics::CONNECTOR_CALL_COUNT.add(
-                &metrics::CONTEXT,
-                1,
-                &[
-                    metrics::request::add_attributes("connector", req.connector.to_string()),
-                    metrics::request::add_attributes(
-                        "flow",
-                        std::any::type_name::<T>()
-                            .split("::")
-                            .last()
-                            .unwrap_or_default()
-                            .to_string(),
-                    ),
-                ],
-            );
+            let flow_name = std::any::type_name::<T>()
+                .split("::")
+                .last()
+                .unwrap_or_default();
+            let connector_environment = get_connector_environment(req.test_mode);
+            let mut call_attributes = vec![
+                metrics::request::add_attributes("connector", req.connector.to_string()),
+                metrics::request::add_attributes("flow", flow_name.to_string()),
+                metrics::request::add_attributes("environment", connector_environment),
+            ];
+            call_attributes.extend(get_correlation_attributes(&req.correlation_metadata));
+            metrics::CONNECTOR_CALL_COUNT.add(&metrics::CONTEXT, 1, &call_attributes);
 
             let connector_request = connector_request.or(connector_integration
                 .build_request(req, &state.conf.connectors)
@@ -278,10 +362,70 @@ where
                 })?);
 
             match connector_request {
-                Some(request) => {
-                    logger::debug!(connector_request=?request);
+                Some(mut request) => {
+                    if request.timeout_secs.is_none() {
+                        request.set_timeout_secs(
+                            state
+                                .conf
+                                .connectors
+                                .get_request_timeout_secs(&req.connector),
+                        );
+                    }
+                    if request.proxy_url.is_none() {
+                        request.set_proxy_url(
+                            state.conf.connectors.get_proxy_url(&req.connector).cloned(),
+                        );
+                    }
+                    logger::debug!(
+                        connector_request=?request,
+                        connector_environment,
+                        correlation_metadata=?req.correlation_metadata
+                    );
+                    let is_capturable = sampling::is_capturable_flow::<T>();
+                    let is_sampled = is_capturable
+                        && sampling::should_capture(
+                            &state.conf.connector_request_sampling,
+                            flow_name,
+                        );
+                    let request_capture = (is_sampled
+                        || (is_capturable
+                            && state.conf.connector_request_sampling.capture_on_failure))
+                        .then(|| sampling::capture_request(&request));
+                    let request_start = Instant::now();
                     let response = call_connector_api(state, request).await;
+                    metrics::CONNECTOR_REQUEST_DURATION.record(
+                        &metrics::CONTEXT,
+                        request_start.elapsed().as_secs_f64(),
+                        &[
+                            metrics::request::add_attributes(
+                                "connector",
+                                req.connector.to_string(),
+                            ),
+                            metrics::request::add_attributes("flow", flow_name.to_string()),
+                            metrics::request::add_attributes(
+                                "status",
+                                if sampling::is_failed_call(&response) {
+                                    "failure"
+                                } else {
+                                    "success"
+                                },
+                            ),
+                        ],
+                    );
                     logger::debug!(connector_response=?response);
+                    if let Some(request_capture) = request_capture
+                        .filter(|_| is_sampled || sampling::is_failed_call(&response))
+                    {
+                        sampling::store_capture(
+                            state,
+                            &req.connector,
+                            flow_name,
+                            &req.attempt_id,
+                            request_capture,
+                            &response,
+                        )
+                        .await;
+                    }
                     match response {
                         Ok(body) => {
                             let response = match body {
@@ -311,6 +455,18 @@ where
                                             req.connector.clone(),
                                         )],
                                     );
+                                    if body.status_code == 429 {
+                                        let retry_after = get_retry_after(
+                                            body.headers.as_ref(),
+                                            time::OffsetDateTime::now_utc(),
+                                        );
+                                        return Err(report!(errors::ConnectorError::RateLimited {
+                                            retry_after
+                                        }))
+                                        .attach_printable_lazy(|| {
+                                            format!("Rate limited by {}", req.connector)
+                                        });
+                                    }
                                     let error = match body.status_code {
                                         500..=511 => {
                                             connector_integration.get_5xx_error_response(body)?
@@ -325,8 +481,18 @@ where
                             };
                             Ok(response)
                         }
-                        Err(error) => Err(error
-                            .change_context(errors::ConnectorError::ProcessingStepFailed(None))),
+                        Err(error) => {
+                            let connector_error = match error.current_context() {
+                                errors::ApiClientError::RequestTimeoutReceived => {
+                                    errors::ConnectorError::RequestTimeout
+                                }
+                                errors::ApiClientError::ResponseTooLarge => {
+                                    errors::ConnectorError::ResponseTooLarge
+                                }
+                                _ => errors::ConnectorError::ProcessingStepFailed(None),
+                            };
+                            Err(error.change_context(connector_error))
+                        }
                     }
                 }
                 None => Ok(router_data),
@@ -335,6 +501,18 @@ where
     }
 }
 
+/// Metric attributes for the low cardinality tags of the correlation metadata, identifiers are
+/// only logged
+fn get_correlation_attributes(
+    correlation_metadata: &types::CorrelationMetadata,
+) -> Vec<router_env::opentelemetry::KeyValue> {
+    correlation_metadata
+        .tags
+        .iter()
+        .map(|(key, value)| router_env::opentelemetry::KeyValue::new(key.clone(), value.clone()))
+        .collect()
+}
+
 #[instrument(skip_all)]
 pub async fn call_connector_api(
     state: &AppState,
@@ -347,7 +525,7 @@ pub async fn call_connector_api(
     let elapsed_time = current_time.elapsed();
     logger::info!(request_time=?elapsed_time);
 
-    handle_response(response).await
+    handle_response(response, state.conf.connector_response.max_body_size_bytes).await
 }
 
 #[instrument(skip_all)]
@@ -365,12 +543,19 @@ pub async fn send_request(
     let should_bypass_proxy = client::proxy_bypass_urls(&state.conf.locker).contains(url);
     let client = client::create_client(
         &state.conf.proxy,
+        request.proxy_url.as_ref(),
         should_bypass_proxy,
         request.certificate,
         request.certificate_key,
     )?;
-    let headers = request.headers.construct_header_map()?;
-    match request.method {
+    let headers = request
+        .headers
+        .construct_header_map(&request.header_order)?;
+    let timeout_secs = request
+        .timeout_secs
+        .or(option_timeout_secs)
+        .unwrap_or(crate::consts::REQUEST_TIME_OUT);
+    let request_builder = match request.method {
         Method::Get => client.get(url),
         Method::Post => {
             let client = client.post(url);
@@ -416,10 +601,17 @@ pub async fn send_request(
         Method::Delete => client.delete(url),
     }
     .add_headers(headers)
-    .timeout(Duration::from_secs(
-        option_timeout_secs.unwrap_or(crate::consts::REQUEST_TIME_OUT),
-    ))
-    .send()
+    .timeout(Duration::from_secs(timeout_secs));
+
+    let connect_retry = &state.conf.connect_retry;
+    send_with_connect_retry(
+        connect_retry.max_attempts,
+        connect_retry.backoff_base_ms,
+        request_builder,
+        reqwest::RequestBuilder::try_clone,
+        |request_builder| request_builder.send(),
+        reqwest::Error::is_connect,
+    )
     .await
     .map_err(|error| match error {
         error if error.is_timeout() => {
@@ -432,9 +624,51 @@ pub async fn send_request(
     .attach_printable("Unable to send request to connector")
 }
 
+/// Send a request, retrying it with an exponential backoff when the connection to the server
+/// could not be established (DNS or connect failures), at most `max_attempts` attempts are made.
+/// Requests are only retried if they can be cloned, which is not the case for streaming bodies.
+async fn send_with_connect_retry<R, T, E, C, S, Fut, F>(
+    max_attempts: u32,
+    backoff_base_ms: u64,
+    mut request: R,
+    try_clone: C,
+    send: S,
+    is_connect_error: F,
+) -> Result<T, E>
+where
+    C: Fn(&R) -> Option<R>,
+    S: Fn(R) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    F: Fn(&E) -> bool,
+{
+    let mut attempt = 1;
+    loop {
+        let retry_request = if attempt < max_attempts {
+            try_clone(&request)
+        } else {
+            None
+        };
+
+        match (send(request).await, retry_request) {
+            (Err(error), Some(retry_request)) if is_connect_error(&error) => {
+                let backoff = Duration::from_millis(
+                    backoff_base_ms.saturating_mul(2_u64.saturating_pow(attempt - 1)),
+                );
+                logger::warn!(attempt, ?backoff, "Retrying request after connect failure");
+                metrics::CONNECT_RETRY_COUNT.add(&metrics::CONTEXT, 1, &[]);
+                tokio::time::sleep(backoff).await;
+                request = retry_request;
+                attempt += 1;
+            }
+            (result, _) => break result,
+        }
+    }
+}
+
 #[instrument(skip_all)]
 async fn handle_response(
     response: CustomResult<reqwest::Response, errors::ApiClientError>,
+    max_body_size_bytes: usize,
 ) -> CustomResult<Result<types::Response, types::Response>, errors::ApiClientError> {
     response
         .map(|response| async {
@@ -446,11 +680,8 @@ async fn handle_response(
                     logger::debug!(response=?response);
                     // If needed add log line
                     // logger:: error!( error_parsing_response=?err);
-                    let response = response
-                        .bytes()
+                    let response = read_response_body(response, max_body_size_bytes)
                         .await
-                        .into_report()
-                        .change_context(errors::ApiClientError::ResponseDecodingFailed)
                         .attach_printable("Error while waiting for response")?;
                     Ok(Ok(types::Response {
                         headers,
@@ -460,11 +691,9 @@ async fn handle_response(
                 }
 
                 status_code @ 500..=599 => {
-                    let bytes = response.bytes().await.map_err(|error| {
-                        report!(error)
-                            .change_context(errors::ApiClientError::ResponseDecodingFailed)
-                            .attach_printable("Client error response received")
-                    })?;
+                    let bytes = read_response_body(response, max_body_size_bytes)
+                        .await
+                        .attach_printable("Client error response received")?;
                     // let error = match status_code {
                     //     500 => errors::ApiClientError::InternalServerErrorReceived,
                     //     502 => errors::ApiClientError::BadGatewayReceived,
@@ -480,11 +709,9 @@ async fn handle_response(
                 }
 
                 status_code @ 400..=499 => {
-                    let bytes = response.bytes().await.map_err(|error| {
-                        report!(error)
-                            .change_context(errors::ApiClientError::ResponseDecodingFailed)
-                            .attach_printable("Client error response received")
-                    })?;
+                    let bytes = read_response_body(response, max_body_size_bytes)
+                        .await
+                        .attach_printable("Client error response received")?;
                     /* let error = match status_code {
                         400 => errors::ApiClientError::BadRequestReceived(bytes),
                         401 => errors::ApiClientError::UnauthorizedReceived(bytes),
@@ -512,6 +739,69 @@ async fn handle_response(
         .await
 }
 
+/// Delay requested by the `Retry-After` header of a rate limited response, given either as a number
+/// of seconds or as an HTTP date
+fn get_retry_after(
+    headers: Option<&reqwest::header::HeaderMap>,
+    current_time: time::OffsetDateTime,
+) -> Option<Duration> {
+    let retry_after = headers?
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    match retry_after.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            let retry_at = time::OffsetDateTime::parse(
+                retry_after,
+                &time::format_description::well_known::Rfc2822,
+            )
+            .ok()?;
+            // A date in the past means that the request can be retried right away
+            Some(Duration::try_from(retry_at - current_time).unwrap_or_default())
+        }
+    }
+}
+
+/// Read the body of the response, without reading more than `max_body_size_bytes` of it into
+/// memory
+async fn read_response_body(
+    mut response: reqwest::Response,
+    max_body_size_bytes: usize,
+) -> CustomResult<bytes::Bytes, errors::ApiClientError> {
+    let exceeds_max_body_size = |content_length: u64| {
+        usize::try_from(content_length)
+            .map_or(true, |content_length| content_length > max_body_size_bytes)
+    };
+
+    if response
+        .content_length()
+        .map_or(false, exceeds_max_body_size)
+    {
+        return Err(report!(errors::ApiClientError::ResponseTooLarge)).attach_printable_lazy(
+            || format!("Content length exceeds {max_body_size_bytes} bytes"),
+        );
+    }
+
+    let mut body = bytes::BytesMut::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .into_report()
+        .change_context(errors::ApiClientError::ResponseDecodingFailed)?
+    {
+        if body.len().saturating_add(chunk.len()) > max_body_size_bytes {
+            return Err(report!(errors::ApiClientError::ResponseTooLarge))
+                .attach_printable_lazy(|| format!("Body exceeds {max_body_size_bytes} bytes"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body.freeze())
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum ApplicationResponse<R> {
     Json(R),
@@ -699,7 +989,10 @@ where
         )
         .respond_to(request)
         .map_into_boxed_body(),
-        Err(error) => log_and_return_error_response(error),
+        Err(error) => log_and_return_error_response(apply_connector_error_status(
+            error,
+            &state.conf().connector_error_status,
+        )),
     };
 
     let response_code = res.status().as_u16();
@@ -714,6 +1007,33 @@ where
     res
 }
 
+/// Environment of the merchant connector account used for a connector call, so that test calls
+/// can be told apart from live calls in logs and metrics
+fn get_connector_environment(test_mode: Option<bool>) -> &'static str {
+    match test_mode {
+        Some(true) => "test",
+        Some(false) => "live",
+        None => "unspecified",
+    }
+}
+
+/// Replace the status code of connector errors with the outward status code configured for its
+/// category, so that the merchant receives a semantically correct status
+fn apply_connector_error_status(
+    mut error: Report<api_models::errors::types::ApiErrorResponse>,
+    connector_error_status: &ConnectorErrorStatus,
+) -> Report<api_models::errors::types::ApiErrorResponse> {
+    if let Some(api_models::errors::types::ApiErrorResponse::ConnectorError(_, status_code)) =
+        error.downcast_mut::<api_models::errors::types::ApiErrorResponse>()
+    {
+        *status_code = http::StatusCode::from_u16(
+            connector_error_status.get_outward_status_code(status_code.as_u16()),
+        )
+        .unwrap_or(*status_code);
+    }
+    error
+}
+
 pub fn log_and_return_error_response<T>(error: Report<T>) -> HttpResponse
 where
     T: error_stack::Context + Clone + ResponseError,
@@ -982,8 +1302,427 @@ pub fn build_redirection_form(
 
 #[cfg(test)]
 mod tests {
+    #![allow(clippy::unwrap_used)]
+
     #[test]
     fn test_mime_essence() {
         assert_eq!(mime::APPLICATION_JSON.essence_str(), "application/json");
     }
+
+    #[test]
+    fn test_connector_environment_tag() {
+        assert_eq!(super::get_connector_environment(Some(true)), "test");
+        assert_eq!(super::get_connector_environment(Some(false)), "live");
+        assert_eq!(super::get_connector_environment(None), "unspecified");
+    }
+
+    #[test]
+    fn test_authentication_rejection_is_detected() {
+        let rejected: Result<(), crate::types::ErrorResponse> = Err(crate::types::ErrorResponse {
+            status_code: 401,
+            ..Default::default()
+        });
+        let forbidden: Result<(), crate::types::ErrorResponse> = Err(crate::types::ErrorResponse {
+            status_code: 403,
+            ..Default::default()
+        });
+
+        assert!(super::is_authentication_rejected(&rejected));
+        assert!(!super::is_authentication_rejected(&forbidden));
+        assert!(!super::is_authentication_rejected::<()>(&Ok(())));
+    }
+
+    #[test]
+    fn test_correlation_attributes_exclude_identifiers() {
+        let correlation_metadata = crate::types::CorrelationMetadata {
+            identifiers: std::collections::HashMap::from([(
+                "customer_id".to_string(),
+                "cus_123".to_string(),
+            )]),
+            tags: std::collections::HashMap::from([(
+                "business_country".to_string(),
+                "US".to_string(),
+            )]),
+        };
+
+        let attributes = super::get_correlation_attributes(&correlation_metadata);
+
+        assert_eq!(attributes.len(), 1);
+        assert_eq!(attributes[0].key.as_str(), "business_country");
+        assert_eq!(attributes[0].value.as_str(), "US");
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum MockSendError {
+        ConnectFailed,
+        Other,
+    }
+
+    async fn send_with_mock_failures(
+        max_attempts: u32,
+        failures: Vec<MockSendError>,
+    ) -> (Result<&'static str, MockSendError>, usize) {
+        let failures = std::sync::Mutex::new(failures.into_iter());
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+        let result = super::send_with_connect_retry(
+            max_attempts,
+            0,
+            (),
+            |request| Some(*request),
+            |_| {
+                attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let next = failures.lock().unwrap().next();
+                async move { next.map_or(Ok("response"), Err) }
+            },
+            |error| *error == MockSendError::ConnectFailed,
+        )
+        .await;
+        (result, attempts.into_inner())
+    }
+
+    #[actix_rt::test]
+    async fn test_connect_failure_is_retried_once() {
+        let (result, attempts) =
+            send_with_mock_failures(2, vec![MockSendError::ConnectFailed]).await;
+        assert_eq!(result, Ok("response"));
+        assert_eq!(attempts, 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_connect_retry_stops_at_max_attempts() {
+        let (result, attempts) = send_with_mock_failures(
+            2,
+            vec![MockSendError::ConnectFailed, MockSendError::ConnectFailed],
+        )
+        .await;
+        assert_eq!(result, Err(MockSendError::ConnectFailed));
+        assert_eq!(attempts, 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_other_errors_are_not_retried() {
+        let (result, attempts) = send_with_mock_failures(2, vec![MockSendError::Other]).await;
+        assert_eq!(result, Err(MockSendError::Other));
+        assert_eq!(attempts, 1);
+    }
+
+    fn get_retry_after_headers(retry_after: &'static str) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::RETRY_AFTER,
+            reqwest::header::HeaderValue::from_static(retry_after),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_retry_after_in_seconds() {
+        let headers = get_retry_after_headers("120");
+        assert_eq!(
+            super::get_retry_after(Some(&headers), time::OffsetDateTime::UNIX_EPOCH),
+            Some(std::time::Duration::from_secs(120))
+        );
+    }
+
+    #[test]
+    fn test_retry_after_http_date() {
+        let headers = get_retry_after_headers("Wed, 21 Oct 2015 07:28:00 GMT");
+        let current_time = time::macros::datetime!(2015-10-21 07:27:30 UTC);
+        assert_eq!(
+            super::get_retry_after(Some(&headers), current_time),
+            Some(std::time::Duration::from_secs(30))
+        );
+
+        // A date in the past allows retrying right away
+        let current_time = time::macros::datetime!(2015-10-21 08:00:00 UTC);
+        assert_eq!(
+            super::get_retry_after(Some(&headers), current_time),
+            Some(std::time::Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_missing_or_invalid_retry_after() {
+        let headers = get_retry_after_headers("soon");
+        assert_eq!(
+            super::get_retry_after(Some(&headers), time::OffsetDateTime::UNIX_EPOCH),
+            None
+        );
+        assert_eq!(
+            super::get_retry_after(None, time::OffsetDateTime::UNIX_EPOCH),
+            None
+        );
+    }
+
+    fn get_mock_response(body: &'static str) -> reqwest::Response {
+        reqwest::Response::from(http::Response::new(body))
+    }
+
+    #[actix_rt::test]
+    async fn test_response_body_within_limit_is_read() {
+        let body = super::read_response_body(get_mock_response("{\"id\":\"1\"}"), 10)
+            .await
+            .unwrap();
+        assert_eq!(body, bytes::Bytes::from("{\"id\":\"1\"}"));
+    }
+
+    #[actix_rt::test]
+    async fn test_response_body_exceeding_limit_is_rejected() {
+        let error = super::read_response_body(get_mock_response("{\"id\":\"12\"}"), 10)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.current_context(),
+            crate::core::errors::ApiClientError::ResponseTooLarge
+        ));
+    }
+
+    #[derive(Default)]
+    struct MockConnector {
+        /// Access token of every connector call, in the order of the calls
+        access_tokens: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    impl super::ConnectorIntegration<crate::types::api::AccessTokenAuth, (), ()> for MockConnector {
+        fn build_request(
+            &self,
+            req: &crate::types::RouterData<crate::types::api::AccessTokenAuth, (), ()>,
+            _connectors: &super::Connectors,
+        ) -> super::CustomResult<Option<super::Request>, super::errors::ConnectorError> {
+            use masking::PeekInterface;
+
+            self.access_tokens.lock().unwrap().push(
+                req.access_token
+                    .as_ref()
+                    .map(|access_token| access_token.token.peek().clone()),
+            );
+            // The response of the router data is returned as the connector response
+            Ok(None)
+        }
+    }
+
+    async fn get_mock_app_state() -> super::AppState {
+        get_mock_app_state_with_conf(Default::default()).await
+    }
+
+    async fn get_mock_app_state_with_conf(
+        conf: crate::configs::settings::Settings,
+    ) -> super::AppState {
+        super::AppState::with_storage(
+            conf,
+            crate::db::StorageImpl::Mock,
+            tokio::sync::oneshot::channel().0,
+        )
+        .await
+    }
+
+    fn get_access_token(token: &str) -> crate::types::AccessToken {
+        crate::types::AccessToken {
+            token: masking::Secret::new(token.to_string()),
+            expires: 3600,
+            access_token_created_at: Some(common_utils::date_time::now_unix_timestamp()),
+        }
+    }
+
+    /// Router data of a connector call with an access token, which the connector rejects with a
+    /// bare 401
+    fn get_rejected_router_data(
+    ) -> crate::types::RouterData<crate::types::api::AccessTokenAuth, (), ()> {
+        crate::types::RouterData {
+            flow: std::marker::PhantomData,
+            merchant_id: "merchant_1".to_string(),
+            customer_id: None,
+            connector_customer: None,
+            connector: "airwallex".to_string(),
+            payment_id: "payment_1".to_string(),
+            attempt_id: "attempt_1".to_string(),
+            status: Default::default(),
+            payment_method: Default::default(),
+            connector_auth_type: Default::default(),
+            description: None,
+            return_url: None,
+            address: Default::default(),
+            auth_type: Default::default(),
+            connector_meta_data: None,
+            amount_captured: None,
+            access_token: Some(get_access_token("rejected")),
+            session_token: None,
+            reference_id: None,
+            payment_method_token: None,
+            preprocessing_id: None,
+            test_mode: None,
+            merchant_connector_id: Some("mca_1".to_string()),
+            correlation_metadata: Default::default(),
+            request: (),
+            response: Err(super::ErrorResponse {
+                status_code: 401,
+                ..Default::default()
+            }),
+            payment_method_id: None,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_rejected_access_token_is_refreshed_and_retried_once() {
+        let state = get_mock_app_state().await;
+        // Access token already refreshed by a concurrent request, reused instead of calling the
+        // access token endpoint of the connector
+        state
+            .store
+            .set_access_token("merchant_1", "airwallex", get_access_token("refreshed"))
+            .await
+            .unwrap();
+        let connector = MockConnector::default();
+
+        let router_data = super::execute_connector_processing_step(
+            &state,
+            super::ConnectorIntegrationAny::get_connector_integration(&connector),
+            &get_rejected_router_data(),
+            crate::core::payments::CallConnectorAction::Trigger,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            *connector.access_tokens.lock().unwrap(),
+            vec![Some("rejected".to_string()), Some("refreshed".to_string())]
+        );
+        assert_eq!(router_data.response.unwrap_err().status_code, 401);
+        // A bare 401 does not tell a credentials rejection apart
+        assert!(!state
+            .store
+            .is_invalid_credentials_marker_set("merchant_1", "mca_1")
+            .await
+            .unwrap());
+    }
+
+    /// Router data of a connector call without an access token, which the connector rejects with
+    /// the error code airwallex uses for invalid credentials
+    fn get_credentials_rejected_router_data(
+    ) -> crate::types::RouterData<crate::types::api::AccessTokenAuth, (), ()> {
+        crate::types::RouterData {
+            access_token: None,
+            response: Err(super::ErrorResponse {
+                code: "credentials_invalid".to_string(),
+                status_code: 401,
+                ..Default::default()
+            }),
+            ..get_rejected_router_data()
+        }
+    }
+
+    async fn call_connector(
+        state: &super::AppState,
+        connector: &MockConnector,
+        router_data: &crate::types::RouterData<crate::types::api::AccessTokenAuth, (), ()>,
+    ) -> Result<(), super::ErrorResponse> {
+        super::execute_connector_processing_step(
+            state,
+            super::ConnectorIntegrationAny::get_connector_integration(connector),
+            router_data,
+            crate::core::payments::CallConnectorAction::Trigger,
+            None,
+        )
+        .await
+        .unwrap()
+        .response
+    }
+
+    #[actix_rt::test]
+    async fn test_connector_is_not_called_while_credentials_are_marked_invalid() {
+        let state = get_mock_app_state().await;
+        let connector = MockConnector::default();
+        let router_data = get_credentials_rejected_router_data();
+
+        let first_response = call_connector(&state, &connector, &router_data).await;
+        assert_eq!(first_response.unwrap_err().code, "credentials_invalid");
+        assert_eq!(connector.access_tokens.lock().unwrap().len(), 1);
+
+        let second_response = call_connector(&state, &connector, &router_data).await;
+        assert_eq!(
+            second_response.unwrap_err().code,
+            crate::consts::INVALID_CREDENTIALS_ERROR_CODE
+        );
+        assert_eq!(connector.access_tokens.lock().unwrap().len(), 1);
+
+        // The marker is kept per merchant connector account
+        let other_router_data = crate::types::RouterData {
+            merchant_connector_id: Some("mca_2".to_string()),
+            ..get_credentials_rejected_router_data()
+        };
+        call_connector(&state, &connector, &other_router_data)
+            .await
+            .unwrap_err();
+        assert_eq!(connector.access_tokens.lock().unwrap().len(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_connector_is_called_again_once_the_marker_expires() {
+        let mut conf = crate::configs::settings::Settings::default();
+        conf.invalid_credentials.marker_ttl_secs = 1;
+        let state = get_mock_app_state_with_conf(conf).await;
+        let connector = MockConnector::default();
+        let router_data = get_credentials_rejected_router_data();
+
+        call_connector(&state, &connector, &router_data)
+            .await
+            .unwrap_err();
+        call_connector(&state, &connector, &router_data)
+            .await
+            .unwrap_err();
+        assert_eq!(connector.access_tokens.lock().unwrap().len(), 1);
+
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        let response = call_connector(&state, &connector, &router_data).await;
+        assert_eq!(response.unwrap_err().code, "credentials_invalid");
+        assert_eq!(connector.access_tokens.lock().unwrap().len(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_connector_without_credentials_error_codes_is_never_marked() {
+        let state = get_mock_app_state().await;
+        let connector = MockConnector::default();
+        let router_data = crate::types::RouterData {
+            connector: "stripe".to_string(),
+            ..get_credentials_rejected_router_data()
+        };
+
+        call_connector(&state, &connector, &router_data)
+            .await
+            .unwrap_err();
+        call_connector(&state, &connector, &router_data)
+            .await
+            .unwrap_err();
+        assert_eq!(connector.access_tokens.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_rate_limited_payment_uses_configured_status() {
+        use common_utils::errors::ReportSwitchExt;
+
+        use crate::core::errors::ConnectorErrorExt;
+
+        let error = Err::<(), _>(error_stack::report!(
+            super::errors::ConnectorError::RateLimited { retry_after: None }
+        ))
+        .to_payment_failed_response();
+        assert!(matches!(
+            error.as_ref().map_err(|error| error.current_context()),
+            Err(super::errors::ApiErrorResponse::ConnectorRateLimited)
+        ));
+
+        let connector_error_status = super::ConnectorErrorStatus {
+            rate_limited: 503,
+            ..Default::default()
+        };
+        let error = super::apply_connector_error_status(
+            error.switch().unwrap_err(),
+            &connector_error_status,
+        );
+        assert_eq!(
+            actix_web::ResponseError::status_code(error.current_context()).as_u16(),
+            503
+        );
+    }
 }
@@ -1,5 +1,8 @@
+use std::{collections::HashMap, sync::Mutex};
+
 use error_stack::{IntoReport, ResultExt};
-use once_cell::sync::OnceCell;
+use masking::{PeekInterface, Secret};
+use once_cell::sync::{Lazy, OnceCell};
 
 use crate::{
     configs::settings::{Locker, Proxy},
@@ -11,6 +14,9 @@ use crate::{
 
 static NON_PROXIED_CLIENT: OnceCell<reqwest::Client> = OnceCell::new();
 static PROXIED_CLIENT: OnceCell<reqwest::Client> = OnceCell::new();
+/// Clients of the connectors with their own proxy, keyed by the proxy URL
+static CONNECTOR_PROXIED_CLIENTS: Lazy<Mutex<HashMap<String, reqwest::Client>>> =
+    Lazy::new(Default::default);
 
 fn get_client_builder(
     proxy_config: &Proxy,
@@ -66,14 +72,44 @@ fn get_base_client(
     .clone())
 }
 
+fn get_connector_proxied_client(
+    connector_proxy_config: &Proxy,
+    proxy_url: &str,
+) -> CustomResult<reqwest::Client, errors::ApiClientError> {
+    let mut clients = CONNECTOR_PROXIED_CLIENTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(client) = clients.get(proxy_url) {
+        return Ok(client.clone());
+    }
+
+    // The proxy URL is not attached to the error, as it may contain credentials
+    let client = get_client_builder(connector_proxy_config, false)?
+        .build()
+        .into_report()
+        .change_context(errors::ApiClientError::ClientConstructionFailed)
+        .attach_printable("Failed to construct connector proxied client")?;
+    clients.insert(proxy_url.to_owned(), client.clone());
+    Ok(client)
+}
+
 // We may need to use outbound proxy to connect to external world.
-// Precedence will be the environment variables, followed by the config.
+// Precedence will be the environment variables, followed by the connector proxy, followed by the
+// config.
 pub(super) fn create_client(
     proxy_config: &Proxy,
+    connector_proxy_url: Option<&Secret<String>>,
     should_bypass_proxy: bool,
     client_certificate: Option<String>,
     client_certificate_key: Option<String>,
 ) -> CustomResult<reqwest::Client, errors::ApiClientError> {
+    let connector_proxy_config = connector_proxy_url.map(|proxy_url| Proxy {
+        http_url: Some(proxy_url.peek().to_owned()),
+        https_url: Some(proxy_url.peek().to_owned()),
+    });
+    let proxy_config = connector_proxy_config.as_ref().unwrap_or(proxy_config);
+
     match (client_certificate, client_certificate_key) {
         (Some(encoded_certificate), Some(encoded_certificate_key)) => {
             let client_builder = get_client_builder(proxy_config, should_bypass_proxy)?;
@@ -90,7 +126,12 @@ pub(super) fn create_client(
                 .change_context(errors::ApiClientError::ClientConstructionFailed)
                 .attach_printable("Failed to construct client with certificate and certificate key")
         }
-        _ => get_base_client(proxy_config, should_bypass_proxy),
+        _ => match connector_proxy_url {
+            Some(proxy_url) if !should_bypass_proxy => {
+                get_connector_proxied_client(proxy_config, proxy_url.peek())
+            }
+            _ => get_base_client(proxy_config, should_bypass_proxy),
+        },
     }
 }
 
@@ -0,0 +1,90 @@
+use crate::{
+    consts, logger,
+    routes::AppState,
+    types::{self, api, ErrorResponse},
+};
+
+/// Error codes with which the connector rejects the credentials, empty if the connector does not
+/// identify credentials rejections
+fn get_invalid_credentials_error_codes(
+    state: &AppState,
+    connector_name: &str,
+) -> &'static [&'static str] {
+    api::ConnectorData::get_connector_by_name(
+        &state.conf.connectors,
+        connector_name,
+        api::GetToken::Connector,
+    )
+    .map(|connector_data| {
+        connector_data
+            .connector
+            .get_invalid_credentials_error_codes()
+    })
+    .unwrap_or_default()
+}
+
+/// Whether the connector recently rejected the credentials of the merchant connector account of
+/// the call. Only connectors which identify credentials rejections are ever marked, so the store
+/// is not queried for the others.
+/// Failures to read the marker are only logged, so that the connector is called in that case.
+pub(crate) async fn is_marked<F, Req, Resp>(
+    state: &AppState,
+    router_data: &types::RouterData<F, Req, Resp>,
+) -> bool {
+    let merchant_connector_id = match router_data.merchant_connector_id.as_deref() {
+        Some(merchant_connector_id) => merchant_connector_id,
+        None => return false,
+    };
+    if get_invalid_credentials_error_codes(state, &router_data.connector).is_empty() {
+        return false;
+    }
+
+    state
+        .store
+        .is_invalid_credentials_marker_set(&router_data.merchant_id, merchant_connector_id)
+        .await
+        .map_err(|error| logger::error!(invalid_credentials_marker_error=?error))
+        .unwrap_or(false)
+}
+
+/// Remember that the connector rejected the credentials of the merchant connector account of the
+/// call, so that the following calls fail without reaching the connector until the marker expires
+/// or the credentials are updated. Only error codes identified by the connector as a credentials
+/// rejection set the marker.
+pub(crate) async fn mark_if_rejected<F, Req, Resp>(
+    state: &AppState,
+    router_data: &types::RouterData<F, Req, Resp>,
+    error_response: &ErrorResponse,
+) {
+    let ttl = state.conf.invalid_credentials.marker_ttl_secs;
+    let merchant_connector_id = match router_data.merchant_connector_id.as_deref() {
+        Some(merchant_connector_id) if ttl != 0 => merchant_connector_id,
+        _ => return,
+    };
+    if !get_invalid_credentials_error_codes(state, &router_data.connector)
+        .contains(&error_response.code.as_str())
+    {
+        return;
+    }
+
+    logger::warn!(
+        connector = %router_data.connector,
+        merchant_connector_id,
+        "Connector rejected the credentials"
+    );
+    let _ = state
+        .store
+        .set_invalid_credentials_marker(&router_data.merchant_id, merchant_connector_id, ttl)
+        .await
+        .map_err(|error| logger::error!(invalid_credentials_marker_error=?error));
+}
+
+/// Error response returned instead of calling the connector while the credentials are marked
+pub(crate) fn get_error_response() -> ErrorResponse {
+    ErrorResponse {
+        code: consts::INVALID_CREDENTIALS_ERROR_CODE.to_string(),
+        message: consts::INVALID_CREDENTIALS_ERROR_MESSAGE.to_string(),
+        reason: None,
+        status_code: 401,
+    }
+}
@@ -113,6 +113,11 @@ pub struct Request {
     pub certificate: Option<String>,
     pub certificate_key: Option<String>,
     pub form_data: Option<reqwest::multipart::Form>,
+    pub timeout_secs: Option<u64>,
+    /// Names of the headers that have to be sent first, in this order
+    pub header_order: Vec<String>,
+    /// Proxy through which the request is sent instead of the globally configured proxy
+    pub proxy_url: Option<Secret<String>>,
 }
 
 impl Request {
@@ -126,6 +131,9 @@ impl Request {
             certificate: None,
             certificate_key: None,
             form_data: None,
+            timeout_secs: None,
+            header_order: Vec::new(),
+            proxy_url: None,
         }
     }
 
@@ -156,6 +164,14 @@ impl Request {
     pub fn set_form_data(&mut self, form_data: reqwest::multipart::Form) {
         self.form_data = Some(form_data);
     }
+
+    pub fn set_timeout_secs(&mut self, timeout_secs: Option<u64>) {
+        self.timeout_secs = timeout_secs;
+    }
+
+    pub fn set_proxy_url(&mut self, proxy_url: Option<Secret<String>>) {
+        self.proxy_url = proxy_url;
+    }
 }
 
 pub struct RequestBuilder {
@@ -167,6 +183,11 @@ pub struct RequestBuilder {
     pub certificate: Option<String>,
     pub certificate_key: Option<String>,
     pub form_data: Option<reqwest::multipart::Form>,
+    pub timeout_secs: Option<u64>,
+    /// Names of the headers that have to be sent first, in this order
+    pub header_order: Vec<String>,
+    /// Proxy through which the request is sent instead of the globally configured proxy
+    pub proxy_url: Option<Secret<String>>,
 }
 
 impl RequestBuilder {
@@ -180,6 +201,9 @@ impl RequestBuilder {
             certificate: None,
             certificate_key: None,
             form_data: None,
+            timeout_secs: None,
+            header_order: Vec::new(),
+            proxy_url: None,
         }
     }
 
@@ -234,6 +258,21 @@ impl RequestBuilder {
         self
     }
 
+    pub fn timeout_secs(mut self, timeout_secs: Option<u64>) -> Self {
+        self.timeout_secs = timeout_secs;
+        self
+    }
+
+    pub fn header_order(mut self, header_order: Vec<String>) -> Self {
+        self.header_order = header_order;
+        self
+    }
+
+    pub fn proxy_url(mut self, proxy_url: Option<Secret<String>>) -> Self {
+        self.proxy_url = proxy_url;
+        self
+    }
+
     pub fn build(self) -> Request {
         Request {
             method: self.method,
@@ -244,6 +283,9 @@ impl RequestBuilder {
             certificate: self.certificate,
             certificate_key: self.certificate_key,
             form_data: self.form_data,
+            timeout_secs: self.timeout_secs,
+            header_order: self.header_order,
+            proxy_url: self.proxy_url,
         }
     }
 }
@@ -255,18 +297,31 @@ impl Default for RequestBuilder {
 }
 
 pub(super) trait HeaderExt {
+    /// Headers named in `header_order` are added first in that order, the remaining headers follow
+    /// in alphabetical order so that the order of the headers is always deterministic
     fn construct_header_map(
         self,
+        header_order: &[String],
     ) -> CustomResult<reqwest::header::HeaderMap, errors::ApiClientError>;
 }
 
 impl HeaderExt for Headers {
     fn construct_header_map(
         self,
+        header_order: &[String],
     ) -> CustomResult<reqwest::header::HeaderMap, errors::ApiClientError> {
         use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 
-        self.into_iter().fold(
+        let mut headers = self.into_iter().collect::<Vec<_>>();
+        headers.sort_by_cached_key(|(header_name, _)| {
+            let position = header_order
+                .iter()
+                .position(|ordered_header| ordered_header.eq_ignore_ascii_case(header_name))
+                .unwrap_or(header_order.len());
+            (position, header_name.to_lowercase())
+        });
+
+        headers.into_iter().fold(
             Ok(HeaderMap::new()),
             |mut header_map, (header_name, header_value)| {
                 let header_name = HeaderName::from_str(&header_name)
@@ -296,3 +351,45 @@ impl RequestBuilderExt for reqwest::RequestBuilder {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn test_headers_follow_configured_order() {
+        let request = RequestBuilder::new()
+            .header("x-request-id", "request_id")
+            .header("Content-Type", "application/json")
+            .header("authorization", "Bearer token")
+            .header("accept", "application/json")
+            .header("date", "Tue, 15 Nov 1994 08:12:31 GMT")
+            .header_order(vec![
+                "Date".to_string(),
+                "authorization".to_string(),
+                "content-type".to_string(),
+            ])
+            .build();
+
+        let header_map = request
+            .headers
+            .construct_header_map(&request.header_order)
+            .unwrap();
+        let header_names = header_map
+            .keys()
+            .map(|header_name| header_name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            header_names,
+            vec![
+                "date",
+                "authorization",
+                "content-type",
+                "accept",
+                "x-request-id"
+            ]
+        );
+    }
+}
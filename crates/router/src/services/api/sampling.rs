@@ -0,0 +1,387 @@
+use masking::{PeekInterface, Secret};
+use rand::Rng;
+use router_env::{instrument, tracing};
+
+use crate::{
+    configs::settings::ConnectorRequestSampling,
+    core::errors::{self, CustomResult},
+    logger,
+    routes::AppState,
+    services::{api::request::ContentType, RedisConnInterface, Request},
+    types::{self, api},
+};
+
+/// Response headers which are captured as is, other response headers are left out
+const CAPTURED_RESPONSE_HEADERS: [&str; 4] =
+    ["content-type", "retry-after", "request-id", "x-request-id"];
+
+/// Body fields whose value is captured as is when it is a string or a number, they only carry the
+/// outcome of the call and never the data of the customer
+const UNREDACTED_BODY_FIELDS: [&str; 5] = ["status", "code", "error_code", "decline_code", "type"];
+
+#[derive(Debug, serde::Serialize)]
+struct ConnectorCallCapture<'a> {
+    connector: &'a str,
+    flow: &'a str,
+    request: String,
+    request_body: Option<String>,
+    response_status_code: Option<u16>,
+    response_headers: std::collections::BTreeMap<String, String>,
+    response: String,
+}
+
+/// Decide whether the current connector call for the given flow should be captured
+pub(super) fn should_capture(sampling: &ConnectorRequestSampling, flow: &str) -> bool {
+    let sample_rate = sampling
+        .flows
+        .get(flow)
+        .copied()
+        .unwrap_or(sampling.sample_rate);
+    is_sampled(sample_rate)
+}
+
+/// Access token calls are never captured, their requests and responses are made of credentials
+pub(super) fn is_capturable_flow<T: 'static>() -> bool {
+    std::any::TypeId::of::<T>() != std::any::TypeId::of::<api::AccessTokenAuth>()
+}
+
+fn is_sampled(sample_rate: u32) -> bool {
+    sample_rate != 0 && rand::thread_rng().gen_range(0..sample_rate) == 0
+}
+
+/// Whether the connector call failed or the connector returned an error response
+pub(super) fn is_failed_call(
+    response: &CustomResult<Result<types::Response, types::Response>, errors::ApiClientError>,
+) -> bool {
+    !matches!(response, Ok(Ok(_)))
+}
+
+/// The request as captured. Masked headers stay masked and every value of the body is redacted,
+/// except for the outcome fields in `UNREDACTED_BODY_FIELDS`, so that the structure of the body is
+/// kept.
+#[derive(Debug)]
+pub(super) struct RequestCapture {
+    request: String,
+    body: Option<String>,
+}
+
+pub(super) fn capture_request(request: &Request) -> RequestCapture {
+    let mut headers = request.headers.iter().collect::<Vec<_>>();
+    headers.sort_by(|(name, _), (other_name, _)| name.cmp(other_name));
+
+    RequestCapture {
+        request: format!("{} {} {headers:?}", request.method, request.url),
+        body: request.payload.as_ref().map(|payload| {
+            redact_body(
+                payload.peek(),
+                matches!(request.content_type, Some(ContentType::FormUrlEncoded)),
+            )
+        }),
+    }
+}
+
+/// Redact every value of a JSON or url encoded body other than the outcome fields, the body is
+/// masked as a whole if it cannot be parsed
+fn redact_body(body: &str, is_form_url_encoded: bool) -> String {
+    if is_form_url_encoded {
+        if let Ok(fields) = serde_urlencoded::from_str::<Vec<(String, String)>>(body) {
+            return fields
+                .into_iter()
+                .map(|(name, value)| format!("{name}={:?}", Secret::<String>::new(value)))
+                .collect::<Vec<_>>()
+                .join("&");
+        }
+    }
+
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(value) => redact_json_value(value).to_string(),
+        Err(_) => format!("{:?}", Secret::<String>::new(body.to_string())),
+    }
+}
+
+fn is_form_url_encoded_response(response: &types::Response) -> bool {
+    response
+        .headers
+        .as_ref()
+        .and_then(|headers| headers.get(http::header::CONTENT_TYPE))
+        .and_then(|content_type| content_type.to_str().ok())
+        .map_or(false, |content_type| {
+            content_type.starts_with("application/x-www-form-urlencoded")
+        })
+}
+
+fn redact_json_value(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(fields) => fields
+            .into_iter()
+            .map(|(name, value)| match value {
+                serde_json::Value::String(_) | serde_json::Value::Number(_)
+                    if UNREDACTED_BODY_FIELDS.contains(&name.as_str()) =>
+                {
+                    (name, value)
+                }
+                value => (name, redact_json_value(value)),
+            })
+            .collect(),
+        serde_json::Value::Array(values) => values.into_iter().map(redact_json_value).collect(),
+        serde_json::Value::String(_) | serde_json::Value::Number(_) => {
+            serde_json::Value::String(format!("{:?}", Secret::<serde_json::Value>::new(value)))
+        }
+        serde_json::Value::Bool(_) | serde_json::Value::Null => value,
+    }
+}
+
+/// The response as captured, or the error if no response was received. The status code and the
+/// response headers in `CAPTURED_RESPONSE_HEADERS` are kept as is, the body is redacted like the
+/// request body.
+#[derive(Debug)]
+struct ResponseCapture {
+    status_code: Option<u16>,
+    headers: std::collections::BTreeMap<String, String>,
+    body: String,
+}
+
+fn capture_response(
+    response: &CustomResult<Result<types::Response, types::Response>, errors::ApiClientError>,
+) -> ResponseCapture {
+    match response {
+        Ok(Ok(response)) | Ok(Err(response)) => ResponseCapture {
+            status_code: Some(response.status_code),
+            headers: response
+                .headers
+                .iter()
+                .flatten()
+                .filter(|(name, _)| CAPTURED_RESPONSE_HEADERS.contains(&name.as_str()))
+                .filter_map(|(name, value)| {
+                    value
+                        .to_str()
+                        .ok()
+                        .map(|value| (name.to_string(), value.to_string()))
+                })
+                .collect(),
+            body: redact_body(
+                &String::from_utf8_lossy(&response.response),
+                is_form_url_encoded_response(response),
+            ),
+        },
+        Err(error) => ResponseCapture {
+            status_code: None,
+            headers: Default::default(),
+            body: format!("{error:?}"),
+        },
+    }
+}
+
+/// Store the captured request and response in redis for the configured retention period, keyed by
+/// the attempt id. Captures are kept in redis rather than in a diagnostics table, so that they
+/// expire with the retention period without a cleanup job and the connector call never waits on a
+/// database write.
+/// Failing to store a capture must not affect the connector call, so errors are only logged.
+#[instrument(skip_all)]
+pub(super) async fn store_capture(
+    state: &AppState,
+    connector: &str,
+    flow: &str,
+    attempt_id: &str,
+    request: RequestCapture,
+    response: &CustomResult<Result<types::Response, types::Response>, errors::ApiClientError>,
+) {
+    let response = capture_response(response);
+    let capture = ConnectorCallCapture {
+        connector,
+        flow,
+        request: request.request,
+        request_body: request.body,
+        response_status_code: response.status_code,
+        response_headers: response.headers,
+        response: response.body,
+    };
+    let key = format!("connector_capture_{connector}_{flow}_{attempt_id}");
+
+    if let Err(error) = state
+        .store
+        .get_redis_conn()
+        .serialize_and_set_key_with_expiry(
+            &key,
+            capture,
+            state.conf.connector_request_sampling.retention_secs,
+        )
+        .await
+    {
+        logger::error!(connector_capture_error=?error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_sampling_rate_holds() {
+        let sample_rate = 10;
+        let total_calls = 100_000;
+        let sampled_calls = (0..total_calls).filter(|_| is_sampled(sample_rate)).count();
+        let expected_calls = total_calls / 10;
+
+        assert!(sampled_calls > expected_calls * 9 / 10);
+        assert!(sampled_calls < expected_calls * 11 / 10);
+    }
+
+    #[test]
+    fn test_sampling_disabled_and_always_on() {
+        assert!((0..1000).all(|_| !is_sampled(0)));
+        assert!((0..1000).all(|_| is_sampled(1)));
+    }
+
+    #[test]
+    fn test_flow_sample_rate_overrides_default() {
+        let sampling = ConnectorRequestSampling {
+            sample_rate: 0,
+            flows: [("Authorize".to_string(), 1)].into_iter().collect(),
+            retention_secs: 900,
+            capture_on_failure: false,
+        };
+
+        assert!(should_capture(&sampling, "Authorize"));
+        assert!(!should_capture(&sampling, "PSync"));
+    }
+
+    fn get_card_request(content_type: ContentType, body: &str) -> Request {
+        let mut request = Request::new(
+            crate::services::Method::Post,
+            "https://connector.example/payments",
+        );
+        request.add_header(
+            "Authorization",
+            crate::services::api::request::Mask::into_masked("Bearer access_token_1".to_string()),
+        );
+        request.add_content_type(content_type);
+        request.set_body(body.to_string());
+        request
+    }
+
+    #[test]
+    fn test_request_capture_redacts_card_details_and_tokens() {
+        let requests = [
+            get_card_request(
+                ContentType::Json,
+                r#"{"card":{"number":"4035501000000008","cvc":"737","expiry_year":2030},"token":"payment_token_1","capture":true}"#,
+            ),
+            get_card_request(
+                ContentType::FormUrlEncoded,
+                "card%5Bnumber%5D=4035501000000008&card%5Bcvc%5D=737&token=payment_token_1",
+            ),
+            get_card_request(
+                ContentType::Json,
+                "number=4035501000000008;cvc=737;token=payment_token_1",
+            ),
+        ];
+
+        for request in requests {
+            let capture = capture_request(&request);
+            let captured = format!("{} {}", capture.request, capture.body.unwrap());
+
+            for sensitive_value in [
+                "4035501000000008",
+                "737",
+                "2030",
+                "payment_token_1",
+                "access_token_1",
+            ] {
+                assert!(
+                    !captured.contains(sensitive_value),
+                    "{sensitive_value} found in {captured}"
+                );
+            }
+            assert!(captured.contains("https://connector.example/payments"));
+        }
+    }
+
+    #[test]
+    fn test_request_capture_keeps_body_structure() {
+        let redacted_body = redact_body(
+            r#"{"card":{"cvc":"737"},"capture":true,"metadata":null}"#,
+            false,
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&redacted_body).unwrap(),
+            serde_json::json!({
+                "card": { "cvc": "*** serde_json::value::Value ***" },
+                "capture": true,
+                "metadata": null,
+            })
+        );
+        assert_eq!(
+            redact_body("card%5Bcvc%5D=737", true),
+            "card[cvc]=*** alloc::string::String ***"
+        );
+    }
+
+    #[test]
+    fn test_access_token_flow_is_never_captured() {
+        assert!(!is_capturable_flow::<api::AccessTokenAuth>());
+        assert!(is_capturable_flow::<api::Authorize>());
+    }
+
+    #[test]
+    fn test_response_capture_redacts_tokens() {
+        let response = types::Response {
+            headers: None,
+            response: bytes::Bytes::from_static(
+                br#"{"id":"payment_1","status":"SUCCEEDED","payment_method":{"card":{"last4":"0008"}},"client_secret":"secret_1"}"#,
+            ),
+            status_code: 200,
+        };
+
+        let capture = capture_response(&Ok(Ok(response)));
+
+        assert_eq!(capture.status_code, Some(200));
+        for sensitive_value in ["payment_1", "0008", "secret_1"] {
+            assert!(
+                !capture.body.contains(sensitive_value),
+                "{sensitive_value} found in {}",
+                capture.body
+            );
+        }
+        assert!(capture.body.contains("client_secret"));
+    }
+
+    #[test]
+    fn test_response_capture_keeps_outcome_and_allowed_headers() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static("application/json"),
+        );
+        headers.insert("x-request-id", http::HeaderValue::from_static("request_1"));
+        headers.insert(
+            "set-cookie",
+            http::HeaderValue::from_static("session=session_1"),
+        );
+        let response = types::Response {
+            headers: Some(headers),
+            response: bytes::Bytes::from_static(
+                br#"{"code":"validation_error","message":"Invalid card number 4035501000000008","status":"FAILED","details":{"code":"card_declined"}}"#,
+            ),
+            status_code: 400,
+        };
+
+        let capture = capture_response(&Ok(Err(response)));
+
+        assert_eq!(capture.status_code, Some(400));
+        assert_eq!(
+            capture.headers,
+            std::collections::BTreeMap::from([
+                ("content-type".to_string(), "application/json".to_string()),
+                ("x-request-id".to_string(), "request_1".to_string()),
+            ])
+        );
+        let body = serde_json::from_str::<serde_json::Value>(&capture.body).unwrap();
+        assert_eq!(body["code"], "validation_error");
+        assert_eq!(body["status"], "FAILED");
+        assert_eq!(body["details"]["code"], "card_declined");
+        assert!(!capture.body.contains("4035501000000008"));
+    }
+}
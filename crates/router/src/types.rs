@@ -11,7 +11,7 @@ pub mod domain;
 pub mod storage;
 pub mod transformers;
 
-use std::marker::PhantomData;
+use std::{collections::HashMap, marker::PhantomData};
 
 pub use api_models::enums::Connector;
 use common_utils::{pii, pii::Email};
@@ -195,6 +195,14 @@ pub struct RouterData<Flow, Request, Response> {
     pub reference_id: Option<String>,
     pub payment_method_token: Option<String>,
     pub preprocessing_id: Option<String>,
+    /// Whether the merchant connector account is configured for test mode
+    pub test_mode: Option<bool>,
+    /// Merchant connector account used for the connector call, `None` if the connector account
+    /// details were given with the request
+    pub merchant_connector_id: Option<String>,
+    /// Metadata for correlating the connector call with the caller's records, never sent to the
+    /// connector
+    pub correlation_metadata: CorrelationMetadata,
 
     /// Contains flow-specific data required to construct a request and send it to the connector.
     pub request: Request,
@@ -206,6 +214,14 @@ pub struct RouterData<Flow, Request, Response> {
     pub payment_method_id: Option<String>,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct CorrelationMetadata {
+    /// Identifiers such as the order or customer id, added to the logs of the connector call
+    pub identifiers: HashMap<String, String>,
+    /// Low cardinality values, added to the logs and as attributes of the connector call metrics
+    pub tags: HashMap<String, String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PaymentsAuthorizeData {
     pub payment_method_data: payments::PaymentMethodData,
@@ -346,6 +362,10 @@ pub struct VerifyRequestData {
 pub struct AccessTokenRequestData {
     pub app_id: String,
     pub id: Option<String>,
+    /// Scopes requested for the access token, connectors use their default scopes when empty
+    pub scopes: Vec<String>,
+    /// Overrides the grant type used by the connector to create the access token
+    pub grant_type: Option<String>,
     // Add more keys if required
 }
 
@@ -384,8 +404,29 @@ pub struct AddAccessTokenResult {
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct AccessToken {
-    pub token: String,
+    pub token: Secret<String>,
+    /// Lifetime of the access token in seconds
     pub expires: i64,
+    /// Unix timestamp (in seconds) at which the access token was created
+    #[serde(default)]
+    pub access_token_created_at: Option<i64>,
+}
+
+impl AccessToken {
+    /// Unix timestamp (in seconds) at which the access token expires, if its creation time is known
+    pub fn expires_at(&self) -> Option<i64> {
+        self.access_token_created_at
+            .map(|created_at| created_at.saturating_add(self.expires))
+    }
+
+    /// Whether the access token should replace `existing` in the store, the access token which
+    /// expires later is kept
+    pub fn should_replace(&self, existing: &Self) -> bool {
+        match (self.expires_at(), existing.expires_at()) {
+            (Some(expires_at), Some(existing_expires_at)) => expires_at >= existing_expires_at,
+            _ => true,
+        }
+    }
 }
 
 #[derive(serde::Serialize, Debug, Clone)]
@@ -688,14 +729,20 @@ impl TryFrom<ConnectorAuthType> for AccessTokenRequestData {
             ConnectorAuthType::HeaderKey { api_key } => Ok(Self {
                 app_id: api_key,
                 id: None,
+                scopes: Vec::new(),
+                grant_type: None,
             }),
             ConnectorAuthType::BodyKey { api_key, key1 } => Ok(Self {
                 app_id: api_key,
                 id: Some(key1),
+                scopes: Vec::new(),
+                grant_type: None,
             }),
             ConnectorAuthType::SignatureKey { api_key, key1, .. } => Ok(Self {
                 app_id: api_key,
                 id: Some(key1),
+                scopes: Vec::new(),
+                grant_type: None,
             }),
             _ => Err(errors::ApiErrorResponse::InvalidDataValue {
                 field_name: "connector_account_details",
@@ -809,6 +856,9 @@ impl<F1, F2, T1, T2> From<(&RouterData<F1, T1, PaymentsResponseData>, T2)>
             payment_method_token: None,
             preprocessing_id: None,
             connector_customer: data.connector_customer.clone(),
+            test_mode: data.test_mode,
+            merchant_connector_id: data.merchant_connector_id.clone(),
+            correlation_metadata: data.correlation_metadata.clone(),
         }
     }
 }
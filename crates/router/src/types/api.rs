@@ -15,6 +15,7 @@ pub mod webhooks;
 use std::{fmt::Debug, str::FromStr};
 
 use error_stack::{report, IntoReport, ResultExt};
+use strum::IntoEnumIterator;
 
 pub use self::{
     admin::*, api_keys::*, configs::*, customers::*, disputes::*, files::*, payment_methods::*,
@@ -46,6 +47,42 @@ pub trait ConnectorTransactionId: ConnectorCommon + Sync {
     }
 }
 
+/// Flows supported by a connector
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ConnectorCapabilities {
+    pub authorize: bool,
+    pub capture: bool,
+    pub refund: bool,
+    pub payout_create: bool,
+    pub payout_sync: bool,
+    pub webhooks: bool,
+    pub access_token: bool,
+}
+
+impl ConnectorCapabilities {
+    /// Capabilities of a connector which has not declared any flows. Access token support is
+    /// taken from [`api_enums::Connector::supports_access_token`] for any payment method.
+    pub fn for_connector(connector_name: &str) -> Self {
+        Self {
+            access_token: api_enums::Connector::from_str(connector_name)
+                .map(|connector| {
+                    api_enums::PaymentMethod::iter()
+                        .any(|payment_method| connector.supports_access_token(payment_method))
+                })
+                .unwrap_or(false),
+            ..Self::default()
+        }
+    }
+}
+
+/// How the credentials of a connector can be verified without running a payment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectorHealthCheck {
+    /// Create an access token with the credentials
+    AccessToken,
+    NotSupported,
+}
+
 pub trait ConnectorCommon {
     /// Name of the connector (in lowercase).
     fn id(&self) -> &'static str;
@@ -70,6 +107,40 @@ pub trait ConnectorCommon {
     /// The base URL for interacting with the connector's API.
     fn base_url<'a>(&self, connectors: &'a Connectors) -> &'a str;
 
+    /// Validate the authentication details of a merchant connector account when it is created,
+    /// so that misconfigured credentials are reported at onboarding instead of on the first payment.
+    /// Accepts every authentication type by default.
+    fn validate_auth_type(
+        &self,
+        _auth_type: &types::ConnectorAuthType,
+    ) -> CustomResult<(), errors::ConnectorError> {
+        Ok(())
+    }
+
+    /// Flows supported by the connector, so that callers can check them without attempting the
+    /// flow. Only access token support is known by default, other flows have to be declared by
+    /// the connector.
+    fn capabilities(&self) -> ConnectorCapabilities {
+        ConnectorCapabilities::for_connector(self.id())
+    }
+
+    /// How the credentials of the connector can be verified without running a payment. Defaults
+    /// to creating an access token for connectors which use access tokens.
+    fn health_check(&self) -> ConnectorHealthCheck {
+        if self.capabilities().access_token {
+            ConnectorHealthCheck::AccessToken
+        } else {
+            ConnectorHealthCheck::NotSupported
+        }
+    }
+
+    /// Error codes with which the connector rejects the credentials of the merchant connector
+    /// account, so that calls with those credentials can be short-circuited for a while. Empty
+    /// by default, a bare 401 is not enough to tell a credentials rejection apart.
+    fn get_invalid_credentials_error_codes(&self) -> &'static [&'static str] {
+        &[]
+    }
+
     /// common error response for a connector if it is same in all case
     fn build_error_response(
         &self,
@@ -292,4 +363,22 @@ mod test {
         let result = enums::Connector::from_str("Opennode");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_access_token_capability_follows_connector_support() {
+        for connector_name in [
+            "airwallex",
+            "globalpay",
+            "paypal",
+            "payu",
+            "trustpay",
+            "iatapay",
+        ] {
+            let capabilities = ConnectorCapabilities::for_connector(connector_name);
+            assert!(capabilities.access_token, "{connector_name}");
+            assert!(!capabilities.authorize, "{connector_name}");
+        }
+        assert!(!ConnectorCapabilities::for_connector("stripe").access_token);
+        assert!(!ConnectorCapabilities::for_connector("unknown").access_token);
+    }
 }
@@ -1,9 +1,10 @@
 pub use api_models::admin::{
-    MerchantAccountCreate, MerchantAccountDeleteResponse, MerchantAccountResponse,
-    MerchantAccountUpdate, MerchantConnectorCreate, MerchantConnectorDeleteResponse,
-    MerchantConnectorDetails, MerchantConnectorDetailsWrap, MerchantConnectorId,
-    MerchantConnectorResponse, MerchantDetails, MerchantId, PaymentMethodsEnabled,
-    RoutingAlgorithm, StraightThroughAlgorithm, ToggleKVRequest, ToggleKVResponse, WebhookDetails,
+    ConnectorHealthStatus, MerchantAccountCreate, MerchantAccountDeleteResponse,
+    MerchantAccountResponse, MerchantAccountUpdate, MerchantConnectorCreate,
+    MerchantConnectorDeleteResponse, MerchantConnectorDetails, MerchantConnectorDetailsWrap,
+    MerchantConnectorHealthCheckResponse, MerchantConnectorId, MerchantConnectorResponse,
+    MerchantDetails, MerchantId, PaymentMethodsEnabled, RoutingAlgorithm, StraightThroughAlgorithm,
+    ToggleKVRequest, ToggleKVResponse, WebhookDetails,
 };
 use common_utils::ext_traits::ValueExt;
 
@@ -130,6 +130,23 @@ pub trait IncomingWebhook: ConnectorCommon + Sync {
         _request: &IncomingWebhookRequestDetails<'_>,
     ) -> CustomResult<IncomingWebhookEvent, errors::ConnectorError>;
 
+    /// Unique id of the webhook delivery, used to discard duplicate deliveries
+    fn get_webhook_delivery_id(
+        &self,
+        _request: &IncomingWebhookRequestDetails<'_>,
+    ) -> CustomResult<Option<String>, errors::ConnectorError> {
+        Ok(None)
+    }
+
+    /// Time at which the event occurred at the connector as a unix timestamp, used to discard
+    /// events older than the last processed event of the same object
+    fn get_webhook_event_timestamp(
+        &self,
+        _request: &IncomingWebhookRequestDetails<'_>,
+    ) -> CustomResult<Option<i64>, errors::ConnectorError> {
+        Ok(None)
+    }
+
     fn get_webhook_resource_object(
         &self,
         _request: &IncomingWebhookRequestDetails<'_>,
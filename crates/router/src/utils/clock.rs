@@ -0,0 +1,54 @@
+/// Source of the current time, injected so that expiry checks can be tested without sleeping
+pub trait Clock: Send + Sync {
+    /// The current time as a unix timestamp in seconds
+    fn now_unix_timestamp(&self) -> i64;
+}
+
+/// Clock backed by the system time, used outside of tests
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_unix_timestamp(&self) -> i64 {
+        common_utils::date_time::now_unix_timestamp()
+    }
+}
+
+#[cfg(test)]
+pub use mock::MockClock;
+
+#[cfg(test)]
+mod mock {
+    use std::sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    };
+
+    /// Clock which only moves when it is set or advanced. Clones share the same time.
+    #[derive(Debug, Default, Clone)]
+    pub struct MockClock {
+        now: Arc<AtomicI64>,
+    }
+
+    impl MockClock {
+        pub fn new(now: i64) -> Self {
+            Self {
+                now: Arc::new(AtomicI64::new(now)),
+            }
+        }
+
+        pub fn set(&self, now: i64) {
+            self.now.store(now, Ordering::SeqCst);
+        }
+
+        pub fn advance(&self, seconds: i64) {
+            self.now.fetch_add(seconds, Ordering::SeqCst);
+        }
+    }
+
+    impl super::Clock for MockClock {
+        fn now_unix_timestamp(&self) -> i64 {
+            self.now.load(Ordering::SeqCst)
+        }
+    }
+}
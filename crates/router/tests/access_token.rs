@@ -0,0 +1,144 @@
+#![allow(clippy::unwrap_used)]
+
+use router::{configs::settings::Settings, routes};
+
+async fn get_app_state() -> routes::AppState {
+    let (tx, _) = tokio::sync::oneshot::channel();
+    routes::AppState::new(Settings::new().unwrap(), tx).await
+}
+
+#[actix_web::test]
+async fn access_token_lock_is_released_only_by_its_holder() {
+    let state = get_app_state().await;
+    let merchant_id = format!("merchant_{}", uuid::Uuid::new_v4());
+
+    assert!(state
+        .store
+        .acquire_access_token_lock(&merchant_id, "airwallex", "request_1", 10)
+        .await
+        .unwrap());
+    assert!(!state
+        .store
+        .acquire_access_token_lock(&merchant_id, "airwallex", "request_2", 10)
+        .await
+        .unwrap());
+
+    // A request whose lock expired must not release the lock held by another request
+    state
+        .store
+        .release_access_token_lock(&merchant_id, "airwallex", "request_2")
+        .await
+        .unwrap();
+    assert!(!state
+        .store
+        .acquire_access_token_lock(&merchant_id, "airwallex", "request_2", 10)
+        .await
+        .unwrap());
+
+    state
+        .store
+        .release_access_token_lock(&merchant_id, "airwallex", "request_1")
+        .await
+        .unwrap();
+    assert!(state
+        .store
+        .acquire_access_token_lock(&merchant_id, "airwallex", "request_2", 10)
+        .await
+        .unwrap());
+}
+
+#[actix_web::test]
+async fn access_token_lock_expires() {
+    let state = get_app_state().await;
+    let merchant_id = format!("merchant_{}", uuid::Uuid::new_v4());
+
+    assert!(state
+        .store
+        .acquire_access_token_lock(&merchant_id, "airwallex", "request_1", 1)
+        .await
+        .unwrap());
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+    assert!(state
+        .store
+        .acquire_access_token_lock(&merchant_id, "airwallex", "request_2", 1)
+        .await
+        .unwrap());
+}
+
+fn get_access_token(token: &str, created_at: i64) -> router::types::AccessToken {
+    router::types::AccessToken {
+        token: masking::Secret::new(token.to_string()),
+        expires: 3600,
+        access_token_created_at: Some(created_at),
+    }
+}
+
+#[actix_web::test]
+async fn concurrent_access_token_refreshes_keep_newer_token() {
+    use masking::PeekInterface;
+
+    let state = get_app_state().await;
+    let merchant_id = format!("merchant_{}", uuid::Uuid::new_v4());
+    let now = common_utils::date_time::now_unix_timestamp();
+
+    // Both refreshes race to store their access token, the newer one has to be kept
+    // irrespective of which one is stored last
+    let (newer_result, older_result) = tokio::join!(
+        state.store.compare_and_set_access_token(
+            &merchant_id,
+            "airwallex",
+            get_access_token("newer", now)
+        ),
+        state.store.compare_and_set_access_token(
+            &merchant_id,
+            "airwallex",
+            get_access_token("older", now - 100)
+        ),
+    );
+
+    assert_eq!(newer_result.unwrap().token.peek(), "newer");
+    assert_eq!(older_result.unwrap().token.peek(), "newer");
+    assert_eq!(
+        state
+            .store
+            .get_access_token(&merchant_id, "airwallex")
+            .await
+            .unwrap()
+            .unwrap()
+            .token
+            .peek(),
+        "newer"
+    );
+}
+
+#[actix_web::test]
+async fn access_token_without_creation_time_is_replaced() {
+    use masking::PeekInterface;
+
+    let state = get_app_state().await;
+    let merchant_id = format!("merchant_{}", uuid::Uuid::new_v4());
+
+    state
+        .store
+        .set_access_token(
+            &merchant_id,
+            "airwallex",
+            router::types::AccessToken {
+                access_token_created_at: None,
+                ..get_access_token("unknown", 0)
+            },
+        )
+        .await
+        .unwrap();
+    let stored_access_token = state
+        .store
+        .compare_and_set_access_token(
+            &merchant_id,
+            "airwallex",
+            get_access_token("refreshed", common_utils::date_time::now_unix_timestamp()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(stored_access_token.token.peek(), "refreshed");
+}
@@ -76,6 +76,9 @@ fn construct_payment_router_data() -> types::PaymentsAuthorizeRouterData {
         payment_method_token: None,
         connector_customer: None,
         preprocessing_id: None,
+        test_mode: None,
+        merchant_connector_id: None,
+        correlation_metadata: types::CorrelationMetadata::default(),
     }
 }
 
@@ -120,6 +123,9 @@ fn construct_refund_router_data<F>() -> types::RefundsRouterData<F> {
         payment_method_token: None,
         connector_customer: None,
         preprocessing_id: None,
+        test_mode: None,
+        merchant_connector_id: None,
+        correlation_metadata: types::CorrelationMetadata::default(),
     }
 }
 
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use masking::Secret;
+use masking::{PeekInterface, Secret};
 use router::types::{self, api, storage::enums, AccessToken};
 
 use crate::{
@@ -40,8 +40,9 @@ impl Connector for AirwallexTest {
 fn get_access_token() -> Option<AccessToken> {
     match CONNECTOR.get_auth_token() {
         types::ConnectorAuthType::BodyKey { api_key, key1 } => Some(AccessToken {
-            token: api_key,
+            token: Secret::new(api_key),
             expires: key1.parse::<i64>().unwrap(),
+            access_token_created_at: None,
         }),
         _ => None,
     }
@@ -82,6 +83,22 @@ async fn should_only_authorize_payment() {
     assert_eq!(response.status, enums::AttemptStatus::Authorized);
 }
 
+// Creates a new access token when the stored access token has expired, and authorizes a payment with it.
+#[serial_test::serial]
+#[actix_web::test]
+async fn should_refresh_expired_access_token() {
+    let (expired_access_token, response) = CONNECTOR
+        .authorize_payment_with_expired_access_token(payment_method_details(), None)
+        .await
+        .expect("Authorize payment response");
+    let access_token = response
+        .access_token
+        .as_ref()
+        .expect("Refreshed access token");
+    assert_ne!(access_token.token.peek(), expired_access_token.token.peek());
+    assert_eq!(response.status, enums::AttemptStatus::Authorized);
+}
+
 // Captures a payment using the manual capture flow (Non 3DS).
 #[serial_test::serial]
 #[actix_web::test]
@@ -156,9 +173,8 @@ async fn should_void_authorized_payment() {
 }
 
 // Refunds a payment using the manual capture flow (Non 3DS).
-// #[serial_test::serial]
+#[serial_test::serial]
 #[actix_web::test]
-#[ignore]
 async fn should_refund_manually_captured_payment() {
     let response = CONNECTOR
         .capture_payment_and_refund(
@@ -176,9 +192,8 @@ async fn should_refund_manually_captured_payment() {
 }
 
 // Partially refunds a payment using the manual capture flow (Non 3DS).
-// #[serial_test::serial]
+#[serial_test::serial]
 #[actix_web::test]
-#[ignore]
 async fn should_partially_refund_manually_captured_payment() {
     let response = CONNECTOR
         .capture_payment_and_refund(
@@ -199,9 +214,8 @@ async fn should_partially_refund_manually_captured_payment() {
 }
 
 // Synchronizes a refund using the manual capture flow (Non 3DS).
-// #[serial_test::serial]
+#[serial_test::serial]
 #[actix_web::test]
-#[ignore]
 async fn should_sync_manually_captured_refund() {
     let refund_response = CONNECTOR
         .capture_payment_and_refund(
@@ -266,9 +280,8 @@ async fn should_sync_auto_captured_payment() {
 }
 
 // Refunds a payment using the automatic capture flow (Non 3DS).
-// #[serial_test::serial]
+#[serial_test::serial]
 #[actix_web::test]
-#[ignore]
 async fn should_refund_auto_captured_payment() {
     let response = CONNECTOR
         .make_payment_and_refund(payment_method_details(), None, get_default_payment_info())
@@ -281,9 +294,8 @@ async fn should_refund_auto_captured_payment() {
 }
 
 // Partially refunds a payment using the automatic capture flow (Non 3DS).
-// #[serial_test::serial]
+#[serial_test::serial]
 #[actix_web::test]
-#[ignore]
 async fn should_partially_refund_succeeded_payment() {
     let refund_response = CONNECTOR
         .make_payment_and_refund(
@@ -303,9 +315,8 @@ async fn should_partially_refund_succeeded_payment() {
 }
 
 // Creates multiple refunds against a payment using the automatic capture flow (Non 3DS).
-// #[serial_test::serial]
+#[serial_test::serial]
 #[actix_web::test]
-#[ignore]
 async fn should_refund_succeeded_payment_multiple_times() {
     CONNECTOR
         .make_payment_and_multiple_refund(
@@ -320,9 +331,8 @@ async fn should_refund_succeeded_payment_multiple_times() {
 }
 
 // Synchronizes a refund using the automatic capture flow (Non 3DS).
-// #[serial_test::serial]
+#[serial_test::serial]
 #[actix_web::test]
-#[ignore]
 async fn should_sync_refund() {
     let refund_response = CONNECTOR
         .make_payment_and_refund(payment_method_details(), None, get_default_payment_info())
@@ -348,92 +358,60 @@ async fn should_sync_refund() {
 #[serial_test::serial]
 #[actix_web::test]
 async fn should_fail_payment_for_incorrect_card_number() {
-    let response = CONNECTOR
-        .make_payment(
-            Some(types::PaymentsAuthorizeData {
-                payment_method_data: types::api::PaymentMethodData::Card(api::Card {
-                    card_number: cards::CardNumber::from_str("1234567891011").unwrap(),
-                    ..utils::CCardType::default().0
-                }),
-                ..utils::PaymentAuthorizeType::default().0
-            }),
+    let error = CONNECTOR
+        .expect_connector_error(CONNECTOR.make_payment(
+            Some(utils::card_details(|card| {
+                card.card_number = cards::CardNumber::from_str("1234567891011").unwrap()
+            })),
             get_default_payment_info(),
-        )
-        .await
-        .unwrap();
-    assert_eq!(
-        response.response.unwrap_err().message,
-        "Invalid card number".to_string(),
-    );
+        ))
+        .await;
+    assert_eq!(error.message, "Invalid card number");
 }
 
 // Creates a payment with incorrect CVC.
 #[serial_test::serial]
 #[actix_web::test]
 async fn should_fail_payment_for_incorrect_cvc() {
-    let response = CONNECTOR
-        .make_payment(
-            Some(types::PaymentsAuthorizeData {
-                payment_method_data: types::api::PaymentMethodData::Card(api::Card {
-                    card_cvc: Secret::new("12345".to_string()),
-                    ..utils::CCardType::default().0
-                }),
-                ..utils::PaymentAuthorizeType::default().0
-            }),
+    let error = CONNECTOR
+        .expect_connector_error(CONNECTOR.make_payment(
+            Some(utils::card_details(|card| {
+                card.card_cvc = Secret::new("12345".to_string())
+            })),
             get_default_payment_info(),
-        )
-        .await
-        .unwrap();
-    assert_eq!(
-        response.response.unwrap_err().message,
-        "Invalid card cvc".to_string(),
-    );
+        ))
+        .await;
+    assert_eq!(error.message, "Invalid card cvc");
 }
 
 // Creates a payment with incorrect expiry month.
 #[serial_test::serial]
 #[actix_web::test]
 async fn should_fail_payment_for_invalid_exp_month() {
-    let response = CONNECTOR
-        .make_payment(
-            Some(types::PaymentsAuthorizeData {
-                payment_method_data: types::api::PaymentMethodData::Card(api::Card {
-                    card_exp_month: Secret::new("20".to_string()),
-                    ..utils::CCardType::default().0
-                }),
-                ..utils::PaymentAuthorizeType::default().0
-            }),
+    let error = CONNECTOR
+        .expect_connector_error(CONNECTOR.make_payment(
+            Some(utils::card_details(|card| {
+                card.card_exp_month = Secret::new("20".to_string())
+            })),
             get_default_payment_info(),
-        )
-        .await
-        .unwrap();
-    assert_eq!(
-        response.response.unwrap_err().message,
-        "Invalid expiry month".to_string(),
-    );
+        ))
+        .await;
+    assert_eq!(error.message, "Invalid expiry month");
 }
 
 // Creates a payment with incorrect expiry year.
 #[serial_test::serial]
 #[actix_web::test]
 async fn should_fail_payment_for_incorrect_expiry_year() {
-    let response = CONNECTOR
-        .make_payment(
-            Some(types::PaymentsAuthorizeData {
-                payment_method_data: types::api::PaymentMethodData::Card(api::Card {
-                    card_exp_year: Secret::new("2000".to_string()),
-                    ..utils::CCardType::default().0
-                }),
-                ..utils::PaymentAuthorizeType::default().0
-            }),
+    let error = CONNECTOR
+        .expect_connector_error(CONNECTOR.make_payment(
+            Some(utils::card_details(|card| {
+                card.card_exp_year = Secret::new("2000".to_string())
+            })),
             get_default_payment_info(),
-        )
-        .await
-        .unwrap();
-    assert_eq!(
-        response.response.unwrap_err().message,
-        "payment_method.card should not be expired".to_string(),
-    );
+        ))
+        .await;
+    assert_eq!(error.message, "payment_method.card should not be expired");
 }
 
 // Voids a payment using automatic capture flow (Non 3DS).
@@ -447,36 +425,103 @@ async fn should_fail_void_payment_for_auto_capture() {
     assert_eq!(authorize_response.status, enums::AttemptStatus::Charged);
     let txn_id = utils::get_connector_transaction_id(authorize_response.response);
     assert_ne!(txn_id, None, "Empty connector transaction id");
-    let void_response = CONNECTOR
-        .void_payment(txn_id.unwrap(), None, get_default_payment_info())
+    let error = CONNECTOR
+        .expect_connector_error(CONNECTOR.void_payment(
+            txn_id.unwrap(),
+            None,
+            get_default_payment_info(),
+        ))
+        .await;
+    utils::assert_error_contains(&error, "is invalid for operation cancel");
+}
+
+// Voids a payment whose confirmation failed, the void error is only returned once the
+// confirmation succeeded with automatic capture.
+#[serial_test::serial]
+#[actix_web::test]
+async fn should_void_payment_after_failed_confirm() {
+    let authorize_response = CONNECTOR
+        .make_payment(
+            Some(utils::card_details(|card| {
+                card.card_number = cards::CardNumber::from_str("1234567891011").unwrap()
+            })),
+            get_default_payment_info(),
+        )
         .await
         .unwrap();
-    assert_eq!(
-        void_response.response.unwrap_err().message,
-        "The PaymentIntent status SUCCEEDED is invalid for operation cancel."
-    );
+    assert!(authorize_response.response.is_err());
+    let payment_intent_id = authorize_response
+        .reference_id
+        .expect("Payment intent id of the failed confirm");
+    let response = CONNECTOR
+        .void_payment(payment_intent_id, None, get_default_payment_info())
+        .await
+        .expect("Void payment response");
+    assert_eq!(response.status, enums::AttemptStatus::Voided);
 }
 
-// Captures a payment using invalid connector payment id.
+// Confirms a payment intent with automatic capture, the void error is returned once the confirm
+// succeeded.
 #[serial_test::serial]
 #[actix_web::test]
-async fn should_fail_capture_for_invalid_payment() {
-    let capture_response = CONNECTOR
-        .capture_payment("123456789".to_string(), None, get_default_payment_info())
+async fn should_fail_void_payment_after_successful_auto_capture_confirm() {
+    let authorize_response = CONNECTOR
+        .make_payment(payment_method_details(), get_default_payment_info())
         .await
         .unwrap();
-    assert_eq!(
-        capture_response.response.unwrap_err().message,
-        String::from(
-            "The requested endpoint does not exist [/api/v1/pa/payment_intents/123456789/capture]"
-        )
+    assert_eq!(authorize_response.status, enums::AttemptStatus::Charged);
+    let payment_intent_id = authorize_response
+        .reference_id
+        .clone()
+        .expect("Payment intent id of the confirm");
+    let txn_id = utils::get_connector_transaction_id(authorize_response.response)
+        .expect("Connector transaction id of the successful confirm");
+    assert_eq!(txn_id, payment_intent_id);
+    let error = CONNECTOR
+        .expect_connector_error(CONNECTOR.void_payment(txn_id, None, get_default_payment_info()))
+        .await;
+    utils::assert_error_contains(
+        &error,
+        "The PaymentIntent status SUCCEEDED is invalid for operation cancel",
     );
 }
 
+// Creates a payment for which the payment intent cannot be created, the payment is not confirmed.
+#[serial_test::serial]
+#[actix_web::test]
+async fn should_fail_payment_when_intent_creation_fails() {
+    let response = CONNECTOR
+        .make_payment(
+            Some(types::PaymentsAuthorizeData {
+                amount: 0,
+                ..payment_method_details().unwrap()
+            }),
+            get_default_payment_info(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status, enums::AttemptStatus::Failure);
+    assert_eq!(response.reference_id, None);
+    assert!(response.response.is_err());
+}
+
+// Captures a payment using invalid connector payment id.
+#[serial_test::serial]
+#[actix_web::test]
+async fn should_fail_capture_for_invalid_payment() {
+    let error = CONNECTOR
+        .expect_connector_error(CONNECTOR.capture_payment(
+            "123456789".to_string(),
+            None,
+            get_default_payment_info(),
+        ))
+        .await;
+    utils::assert_error_contains(&error, "The requested endpoint does not exist");
+}
+
 // Refunds a payment with refund amount higher than payment amount.
-// #[serial_test::serial]
+#[serial_test::serial]
 #[actix_web::test]
-#[ignore]
 async fn should_fail_for_refund_amount_higher_than_payment_amount() {
     let response = CONNECTOR
         .make_payment_and_refund(
@@ -489,12 +534,103 @@ async fn should_fail_for_refund_amount_higher_than_payment_amount() {
         )
         .await
         .unwrap();
+    let error = response
+        .response
+        .expect_err("Refund amount higher than payment amount should fail");
+    assert_eq!(error.status_code, 400);
+    utils::assert_error_contains(&error, "amount");
+}
+
+// Connector dependent test cases goes here
+
+// Rejects connector account details that cannot be used to obtain an access token.
+#[test]
+fn should_validate_auth_type() {
+    use router::types::api::ConnectorCommon;
+
+    let connector = router::connector::Airwallex;
+    assert!(connector
+        .validate_auth_type(&types::ConnectorAuthType::BodyKey {
+            api_key: "api_key".to_string(),
+            key1: "client_id".to_string(),
+        })
+        .is_ok());
+    assert!(connector
+        .validate_auth_type(&types::ConnectorAuthType::BodyKey {
+            api_key: "api_key".to_string(),
+            key1: String::new(),
+        })
+        .is_err());
+    assert!(connector
+        .validate_auth_type(&types::ConnectorAuthType::HeaderKey {
+            api_key: "api_key".to_string(),
+        })
+        .is_err());
+}
+
+// Reports access token based payments, refunds and webhooks, but no payouts.
+#[test]
+fn should_report_capabilities() {
+    use router::types::api::ConnectorCommon;
+
+    let capabilities = router::connector::Airwallex.capabilities();
+    assert!(capabilities.authorize);
+    assert!(capabilities.capture);
+    assert!(capabilities.refund);
+    assert!(capabilities.webhooks);
+    assert!(capabilities.access_token);
+    assert!(!capabilities.payout_create);
+    assert!(!capabilities.payout_sync);
+}
+
+// Verifies that the credentials of Airwallex are checked by creating an access token.
+#[test]
+fn should_health_check_with_access_token() {
+    use router::types::api::{ConnectorCommon, ConnectorHealthCheck};
+
+    assert_eq!(
+        router::connector::Airwallex.health_check(),
+        ConnectorHealthCheck::AccessToken
+    );
     assert_eq!(
-        response.response.unwrap_err().message,
-        "Refund amount (₹1.50) is greater than charge amount (₹1.00)",
+        router::connector::Stripe.health_check(),
+        ConnectorHealthCheck::NotSupported
     );
 }
 
-// Connector dependent test cases goes here
+// Creates a payment with a 3DS enrolled card and expects the customer to be redirected.
+#[serial_test::serial]
+#[actix_web::test]
+async fn should_authorize_3ds_payment() {
+    let response = CONNECTOR
+        .authorize_payment(
+            Some(types::PaymentsAuthorizeData {
+                payment_method_data: types::api::PaymentMethodData::Card(api::Card {
+                    card_number: cards::CardNumber::from_str("4012000300000005").unwrap(),
+                    card_exp_month: Secret::new("02".to_string()),
+                    card_exp_year: Secret::new("2035".to_string()),
+                    card_holder_name: Secret::new("John Doe".to_string()),
+                    card_cvc: Secret::new("123".to_string()),
+                    card_issuer: None,
+                    card_network: None,
+                }),
+                ..payment_method_details().unwrap()
+            }),
+            Some(utils::PaymentInfo {
+                auth_type: Some(enums::AuthenticationType::ThreeDs),
+                ..get_default_payment_info().unwrap()
+            }),
+        )
+        .await
+        .expect("Authorize payment response");
+    assert_eq!(response.status, enums::AttemptStatus::AuthenticationPending);
+    let redirection_data = match response.response.unwrap() {
+        types::PaymentsResponseData::TransactionResponse {
+            redirection_data, ..
+        } => redirection_data,
+        _ => None,
+    };
+    assert!(redirection_data.is_some());
+}
 
 // [#478]: add unit tests for non 3DS, wallets & webhooks in connector tests
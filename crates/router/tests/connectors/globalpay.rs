@@ -42,8 +42,9 @@ impl Connector for Globalpay {
 fn get_access_token() -> Option<AccessToken> {
     match utils::Connector::get_auth_token(&CONNECTOR) {
         ConnectorAuthType::BodyKey { api_key, key1: _ } => Some(AccessToken {
-            token: api_key,
+            token: Secret::new(api_key),
             expires: 18600,
+            access_token_created_at: None,
         }),
         _ => None,
     }
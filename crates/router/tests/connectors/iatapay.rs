@@ -42,8 +42,9 @@ fn get_access_token() -> Option<AccessToken> {
             key1: _,
             api_secret: _,
         } => Some(AccessToken {
-            token: api_key,
+            token: Secret::new(api_key),
             expires: 60 * 5,
+            access_token_created_at: None,
         }),
         _ => None,
     }
@@ -106,8 +107,8 @@ async fn should_fail_for_refund_on_unsuccessed_payment() {
         .authorize_payment(payment_method_details(), get_default_payment_info())
         .await
         .expect("Authorize payment response");
-    let refund_response = CONNECTOR
-        .refund_payment(
+    let error = CONNECTOR
+        .expect_connector_error(CONNECTOR.refund_payment(
             get_connector_transaction_id(response.response).unwrap(),
             Some(types::RefundsData {
                 refund_amount: response.request.amount,
@@ -115,13 +116,9 @@ async fn should_fail_for_refund_on_unsuccessed_payment() {
                 ..utils::PaymentRefundType::default().0
             }),
             get_default_payment_info(),
-        )
-        .await
-        .unwrap();
-    assert_eq!(
-        refund_response.response.unwrap_err().code,
-        "BAD_REQUEST".to_string(),
-    );
+        ))
+        .await;
+    utils::assert_error_code(&error, "BAD_REQUEST");
 }
 
 // Refunds a payment with refund amount higher than payment amount.
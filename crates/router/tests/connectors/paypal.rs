@@ -39,8 +39,9 @@ fn get_access_token() -> Option<AccessToken> {
 
     match connector.get_auth_token() {
         ConnectorAuthType::BodyKey { api_key, key1: _ } => Some(AccessToken {
-            token: api_key,
+            token: Secret::new(api_key),
             expires: 18600,
+            access_token_created_at: None,
         }),
         _ => None,
     }
@@ -1,3 +1,4 @@
+use masking::Secret;
 use router::types::{self, api, storage::enums, AccessToken, ConnectorAuthType};
 
 use crate::{
@@ -33,8 +34,9 @@ fn get_access_token() -> Option<AccessToken> {
     let connector = Payu {};
     match connector.get_auth_token() {
         ConnectorAuthType::BodyKey { api_key, key1 } => Some(AccessToken {
-            token: api_key,
+            token: Secret::new(api_key),
             expires: key1.parse::<i64>().unwrap(),
+            access_token_created_at: None,
         }),
         _ => None,
     }
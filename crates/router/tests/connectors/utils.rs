@@ -1,4 +1,4 @@
-use std::{fmt::Debug, marker::PhantomData, str::FromStr, time::Duration};
+use std::{fmt::Debug, future::Future, marker::PhantomData, str::FromStr, time::Duration};
 
 use async_trait::async_trait;
 use error_stack::Report;
@@ -6,9 +6,9 @@ use masking::Secret;
 use router::{
     configs::settings::Settings,
     core::{errors, errors::ConnectorError, payments},
-    db::StorageImpl,
+    db::{merchant_connector_account::ConnectorAccessToken, StorageImpl},
     routes, services,
-    types::{self, api, storage::enums, AccessToken, PaymentAddress, RouterData},
+    types::{self, api, domain, storage::enums, AccessToken, PaymentAddress, RouterData},
 };
 use tokio::sync::oneshot;
 use wiremock::{Mock, MockServer};
@@ -64,6 +64,67 @@ pub trait ConnectorActions: Connector {
         call_connector(request, integration).await
     }
 
+    /// Stores an expired access token for the connector and authorizes a payment with the access
+    /// token obtained through `add_access_token`, which is expected to create a new access token.
+    /// Returns the expired access token along with the authorized payment.
+    async fn authorize_payment_with_expired_access_token(
+        &self,
+        payment_data: Option<types::PaymentsAuthorizeData>,
+        payment_info: Option<PaymentInfo>,
+    ) -> Result<(AccessToken, types::PaymentsAuthorizeRouterData), Report<ConnectorError>> {
+        let connector = self.get_data();
+        let integration = connector.connector.get_connector_integration();
+        let mut request = self.generate_data(
+            types::PaymentsAuthorizeData {
+                confirm: true,
+                capture_method: Some(storage_models::enums::CaptureMethod::Manual),
+                ..(payment_data.unwrap_or(PaymentAuthorizeType::default().0))
+            },
+            payment_info,
+        );
+        let tx: oneshot::Sender<()> = oneshot::channel().0;
+        let state = routes::AppState::with_storage(
+            Settings::new().unwrap(),
+            StorageImpl::PostgresqlTest,
+            tx,
+        )
+        .await;
+
+        // Created an hour ago with a lifetime of a minute
+        let expired_access_token = AccessToken {
+            token: Secret::new("expired_access_token".to_string()),
+            expires: 60,
+            access_token_created_at: Some(common_utils::date_time::now_unix_timestamp() - 3600),
+        };
+        state
+            .store
+            .set_access_token(
+                &request.merchant_id,
+                connector.connector.id(),
+                expired_access_token.clone(),
+            )
+            .await
+            .map_err(|error| error.change_context(ConnectorError::ProcessingStepFailed(None)))?;
+
+        let add_access_token_result = payments::access_token::add_access_token(
+            &state,
+            &connector,
+            &get_merchant_account(&request.merchant_id),
+            &request,
+        )
+        .await
+        .map_err(|error| error.change_context(ConnectorError::ProcessingStepFailed(None)))?;
+        payments::access_token::update_router_data_with_access_token_result(
+            &add_access_token_result,
+            &mut request,
+            &payments::CallConnectorAction::Trigger,
+        );
+
+        integration.execute_pretasks(&mut request, &state).await?;
+        let response = call_connector(request, integration).await?;
+        Ok((expired_access_token, response))
+    }
+
     /// For initiating payments when `CaptureMethod` is set to `Automatic`
     /// This does complete the transaction without user intervention to Capture the payment
     async fn make_payment(
@@ -362,6 +423,21 @@ pub trait ConnectorActions: Connector {
         Err(errors::ConnectorError::ProcessingStepFailed(None).into())
     }
 
+    /// Runs a connector flow that is expected to be declined by the connector and returns the
+    /// error response of the connector
+    async fn expect_connector_error<Flow, Req, Res, Fut>(&self, flow: Fut) -> types::ErrorResponse
+    where
+        Flow: Send,
+        Req: Send,
+        Res: Debug + Send,
+        Fut: Future<Output = Result<RouterData<Flow, Req, Res>, Report<ConnectorError>>> + Send,
+    {
+        flow.await
+            .expect("Connector flow failed to execute")
+            .response
+            .expect_err("Connector flow was expected to return an error response")
+    }
+
     fn generate_data<Flow, Req: From<Req>, Res>(
         &self,
         req: Req,
@@ -403,6 +479,9 @@ pub trait ConnectorActions: Connector {
             payment_method_token: None,
             connector_customer: None,
             preprocessing_id: None,
+            test_mode: None,
+            merchant_connector_id: None,
+            correlation_metadata: types::CorrelationMetadata::default(),
         }
     }
 
@@ -426,6 +505,32 @@ pub trait ConnectorActions: Connector {
     }
 }
 
+fn get_merchant_account(merchant_id: &str) -> domain::MerchantAccount {
+    domain::MerchantAccount {
+        id: None,
+        merchant_id: merchant_id.to_string(),
+        return_url: None,
+        enable_payment_response_hash: false,
+        payment_response_hash_key: None,
+        redirect_to_merchant_with_http_post: false,
+        merchant_name: None,
+        merchant_details: None,
+        webhook_details: None,
+        sub_merchants_enabled: None,
+        parent_merchant_id: None,
+        publishable_key: None,
+        storage_scheme: enums::MerchantStorageScheme::PostgresOnly,
+        locker_id: None,
+        metadata: None,
+        routing_algorithm: None,
+        primary_business_details: serde_json::json!([]),
+        frm_routing_algorithm: None,
+        created_at: common_utils::date_time::now(),
+        modified_at: common_utils::date_time::now(),
+        intent_fulfillment_time: None,
+    }
+}
+
 async fn call_connector<
     T: Debug + Clone + 'static,
     Req: Debug + Clone + 'static,
@@ -493,6 +598,17 @@ impl Default for CCardType {
     }
 }
 
+/// Authorize request data for a card payment, built from the default test card with `overrides`
+/// applied, eg. `card_details(|card| card.card_cvc = Secret::new("12345".to_string()))`
+pub fn card_details(overrides: impl FnOnce(&mut api::Card)) -> types::PaymentsAuthorizeData {
+    let mut card = CCardType::default().0;
+    overrides(&mut card);
+    types::PaymentsAuthorizeData {
+        payment_method_data: types::api::PaymentMethodData::Card(card),
+        ..PaymentAuthorizeType::default().0
+    }
+}
+
 impl Default for PaymentAuthorizeType {
     fn default() -> Self {
         let data = types::PaymentsAuthorizeData {
@@ -629,3 +745,20 @@ pub fn get_connector_metadata(
         _ => None,
     }
 }
+
+/// Asserts that the error code of the connector matches exactly
+pub fn assert_error_code(error: &types::ErrorResponse, code: &str) {
+    assert_eq!(
+        error.code, code,
+        "Unexpected error code, error response: {error:?}"
+    );
+}
+
+/// Asserts that the error message of the connector contains the given message, so that tests do
+/// not break on minor changes in the wording of the connector's messages
+pub fn assert_error_contains(error: &types::ErrorResponse, message: &str) {
+    assert!(
+        error.message.contains(message),
+        "Error message does not contain {message:?}, error response: {error:?}"
+    );
+}
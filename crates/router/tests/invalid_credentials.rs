@@ -0,0 +1,188 @@
+#![allow(clippy::unwrap_used, clippy::panic)]
+
+use router::{
+    configs::settings::Settings,
+    core::{admin, errors},
+    routes,
+    services::ApplicationResponse,
+};
+
+async fn get_app_state() -> routes::AppState {
+    let (tx, _) = tokio::sync::oneshot::channel();
+    routes::AppState::new(Settings::new().unwrap(), tx).await
+}
+
+/// Create a merchant account with a connector, returning the merchant connector id
+async fn create_merchant_connector_account(
+    state: &routes::AppState,
+    merchant_id: &str,
+    connector_name: &str,
+    connector_account_details: serde_json::Value,
+) -> String {
+    let merchant_account = serde_json::from_value(serde_json::json!({
+        "merchant_id": merchant_id,
+        "primary_business_details": [{ "country": "US", "business": "default" }],
+    }))
+    .unwrap();
+    admin::create_merchant_account(&*state.store, merchant_account)
+        .await
+        .unwrap();
+
+    let merchant_connector_account = serde_json::from_value(serde_json::json!({
+        "connector_type": "fiz_operations",
+        "connector_name": connector_name,
+        "connector_account_details": connector_account_details,
+        "business_country": "US",
+        "business_label": "default",
+    }))
+    .unwrap();
+    match admin::create_payment_connector(
+        &*state.store,
+        &state.conf.connectors,
+        merchant_connector_account,
+        &merchant_id.to_string(),
+    )
+    .await
+    .unwrap()
+    {
+        ApplicationResponse::Json(response) => response.merchant_connector_id,
+        _ => panic!("Unexpected response for the merchant connector account creation"),
+    }
+}
+
+async fn update_merchant_connector_account(
+    state: &routes::AppState,
+    merchant_id: &str,
+    merchant_connector_id: &str,
+    request: serde_json::Value,
+) -> errors::RouterResponse<api_models::admin::MerchantConnectorResponse> {
+    admin::update_payment_connector(
+        &*state.store,
+        &state.conf.connectors,
+        merchant_id,
+        merchant_connector_id,
+        serde_json::from_value(request).unwrap(),
+    )
+    .await
+}
+
+#[actix_web::test]
+async fn invalid_credentials_marker_is_cleared_by_credentials_update() {
+    let state = get_app_state().await;
+    let merchant_id = format!("merchant_{}", uuid::Uuid::new_v4());
+    let merchant_connector_id = create_merchant_connector_account(
+        &state,
+        &merchant_id,
+        "stripe",
+        serde_json::json!({ "auth_type": "HeaderKey", "api_key": "invalid_key" }),
+    )
+    .await;
+    for marked_merchant_connector_id in [merchant_connector_id.as_str(), "mca_other"] {
+        state
+            .store
+            .set_invalid_credentials_marker(&merchant_id, marked_merchant_connector_id, 60)
+            .await
+            .unwrap();
+    }
+
+    // Updates which don't change the credentials keep the marker
+    update_merchant_connector_account(
+        &state,
+        &merchant_id,
+        &merchant_connector_id,
+        serde_json::json!({ "connector_type": "fiz_operations", "metadata": { "city": "NY" } }),
+    )
+    .await
+    .unwrap();
+    assert!(state
+        .store
+        .is_invalid_credentials_marker_set(&merchant_id, &merchant_connector_id)
+        .await
+        .unwrap());
+
+    update_merchant_connector_account(
+        &state,
+        &merchant_id,
+        &merchant_connector_id,
+        serde_json::json!({
+            "connector_type": "fiz_operations",
+            "connector_account_details": { "auth_type": "HeaderKey", "api_key": "updated_key" },
+        }),
+    )
+    .await
+    .unwrap();
+    assert!(!state
+        .store
+        .is_invalid_credentials_marker_set(&merchant_id, &merchant_connector_id)
+        .await
+        .unwrap());
+    // Markers of the other merchant connector accounts are kept
+    assert!(state
+        .store
+        .is_invalid_credentials_marker_set(&merchant_id, "mca_other")
+        .await
+        .unwrap());
+}
+
+#[actix_web::test]
+async fn invalid_credentials_marker_expires_after_its_ttl() {
+    let state = get_app_state().await;
+    let merchant_id = format!("merchant_{}", uuid::Uuid::new_v4());
+    state
+        .store
+        .set_invalid_credentials_marker(&merchant_id, "mca_1", 1)
+        .await
+        .unwrap();
+    assert!(state
+        .store
+        .is_invalid_credentials_marker_set(&merchant_id, "mca_1")
+        .await
+        .unwrap());
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    assert!(!state
+        .store
+        .is_invalid_credentials_marker_set(&merchant_id, "mca_1")
+        .await
+        .unwrap());
+}
+
+#[actix_web::test]
+async fn credentials_update_with_unsupported_auth_type_is_rejected() {
+    let state = get_app_state().await;
+    let merchant_id = format!("merchant_{}", uuid::Uuid::new_v4());
+    let merchant_connector_id = create_merchant_connector_account(
+        &state,
+        &merchant_id,
+        "airwallex",
+        serde_json::json!({ "auth_type": "BodyKey", "api_key": "api_key", "key1": "client_id" }),
+    )
+    .await;
+    state
+        .store
+        .set_invalid_credentials_marker(&merchant_id, &merchant_connector_id, 60)
+        .await
+        .unwrap();
+
+    let error = update_merchant_connector_account(
+        &state,
+        &merchant_id,
+        &merchant_connector_id,
+        serde_json::json!({
+            "connector_type": "fiz_operations",
+            "connector_account_details": { "auth_type": "HeaderKey", "api_key": "updated_key" },
+        }),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        error.current_context(),
+        errors::ApiErrorResponse::InvalidDataFormat { .. }
+    ));
+    // The rejected update neither stores the credentials nor clears the marker
+    assert!(state
+        .store
+        .is_invalid_credentials_marker_set(&merchant_id, &merchant_connector_id)
+        .await
+        .unwrap());
+}
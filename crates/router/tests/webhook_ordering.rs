@@ -0,0 +1,148 @@
+#![allow(clippy::unwrap_used)]
+
+use api_models::{payments::PaymentIdType, webhooks::ObjectReferenceId};
+use router::{
+    configs::settings::{Settings, WebhookOrderingPolicy},
+    core::webhooks::ordering::{self, WebhookDeliveryDetails, WebhookOrderingDecision},
+    routes,
+};
+
+const CONNECTOR: &str = "airwallex";
+
+async fn get_app_state() -> routes::AppState {
+    let mut conf = Settings::new().unwrap();
+    conf.webhook_ordering.connectors.insert(
+        CONNECTOR.to_string(),
+        WebhookOrderingPolicy {
+            deduplicate: true,
+            reject_out_of_order: true,
+        },
+    );
+    let (tx, _) = tokio::sync::oneshot::channel();
+    routes::AppState::new(conf, tx).await
+}
+
+fn get_merchant_id() -> String {
+    format!("merchant_{}", uuid::Uuid::new_v4())
+}
+
+fn get_delivery_details(delivery_id: &str, event_timestamp: i64) -> WebhookDeliveryDetails {
+    WebhookDeliveryDetails::new(
+        Some(delivery_id.to_string()),
+        Some(event_timestamp),
+        &ObjectReferenceId::PaymentId(PaymentIdType::ConnectorTransactionId("int_1".to_string())),
+    )
+}
+
+/// Check the delivery and record it as processed if it was not skipped
+async fn deliver(
+    state: &routes::AppState,
+    merchant_id: &str,
+    delivery_details: &WebhookDeliveryDetails,
+) -> WebhookOrderingDecision {
+    let decision =
+        ordering::check_webhook_ordering(state, merchant_id, CONNECTOR, delivery_details).await;
+    if decision == WebhookOrderingDecision::Process {
+        ordering::record_processed_webhook(state, merchant_id, CONNECTOR, delivery_details).await;
+    }
+    decision
+}
+
+#[actix_web::test]
+async fn duplicate_delivery_is_skipped() {
+    let state = get_app_state().await;
+    let merchant_id = get_merchant_id();
+    let delivery_details = get_delivery_details("evt_1", 100);
+
+    assert_eq!(
+        deliver(&state, &merchant_id, &delivery_details).await,
+        WebhookOrderingDecision::Process
+    );
+    assert_eq!(
+        deliver(&state, &merchant_id, &delivery_details).await,
+        WebhookOrderingDecision::Duplicate
+    );
+}
+
+#[actix_web::test]
+async fn concurrent_duplicate_delivery_is_processed_once() {
+    let state = get_app_state().await;
+    let merchant_id = get_merchant_id();
+    let delivery_details = get_delivery_details("evt_1", 100);
+
+    let (first_decision, second_decision) = tokio::join!(
+        ordering::check_webhook_ordering(&state, &merchant_id, CONNECTOR, &delivery_details),
+        ordering::check_webhook_ordering(&state, &merchant_id, CONNECTOR, &delivery_details),
+    );
+
+    let mut decisions = vec![first_decision, second_decision];
+    decisions.sort_by_key(|decision| *decision == WebhookOrderingDecision::Duplicate);
+    assert_eq!(
+        decisions,
+        vec![
+            WebhookOrderingDecision::Process,
+            WebhookOrderingDecision::Duplicate
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn released_delivery_is_processed_again() {
+    let state = get_app_state().await;
+    let merchant_id = get_merchant_id();
+    let delivery_details = get_delivery_details("evt_1", 100);
+
+    assert_eq!(
+        ordering::check_webhook_ordering(&state, &merchant_id, CONNECTOR, &delivery_details).await,
+        WebhookOrderingDecision::Process
+    );
+    ordering::release_webhook_delivery(&state, &merchant_id, CONNECTOR, &delivery_details).await;
+
+    assert_eq!(
+        deliver(&state, &merchant_id, &delivery_details).await,
+        WebhookOrderingDecision::Process
+    );
+}
+
+#[actix_web::test]
+async fn older_event_is_skipped() {
+    let state = get_app_state().await;
+    let merchant_id = get_merchant_id();
+
+    assert_eq!(
+        deliver(&state, &merchant_id, &get_delivery_details("evt_1", 200)).await,
+        WebhookOrderingDecision::Process
+    );
+    assert_eq!(
+        deliver(&state, &merchant_id, &get_delivery_details("evt_2", 100)).await,
+        WebhookOrderingDecision::OutOfOrder
+    );
+}
+
+#[actix_web::test]
+async fn newer_event_is_processed() {
+    let state = get_app_state().await;
+    let merchant_id = get_merchant_id();
+
+    assert_eq!(
+        deliver(&state, &merchant_id, &get_delivery_details("evt_1", 100)).await,
+        WebhookOrderingDecision::Process
+    );
+    assert_eq!(
+        deliver(&state, &merchant_id, &get_delivery_details("evt_2", 200)).await,
+        WebhookOrderingDecision::Process
+    );
+
+    // Recording an older event processed concurrently keeps the newer event timestamp
+    ordering::record_processed_webhook(
+        &state,
+        &merchant_id,
+        CONNECTOR,
+        &get_delivery_details("evt_3", 150),
+    )
+    .await;
+    assert_eq!(
+        deliver(&state, &merchant_id, &get_delivery_details("evt_4", 180)).await,
+        WebhookOrderingDecision::OutOfOrder
+    );
+}
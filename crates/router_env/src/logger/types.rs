@@ -72,6 +72,8 @@ pub enum Flow {
     MerchantConnectorsDelete,
     /// Merchant Connectors list flow.
     MerchantConnectorsList,
+    /// Merchant Connectors health check flow.
+    MerchantConnectorsHealthCheck,
     /// ConfigKey create flow.
     ConfigKeyCreate,
     /// ConfigKey fetch flow.